@@ -99,7 +99,14 @@ impl ApplicationHandler for App {
 
             // build state
             let window_ptr = std::sync::Arc::new(window);
-            let state = State::new(window_ptr.clone(), self.config.network.take());
+            let state = State::new(
+                window_ptr.clone(),
+                self.config.network.take(),
+                self.config.gltf_path.take(),
+                self.config.script_path.take(),
+                self.config.import_gltf_path.take(),
+                self.config.msaa_samples,
+            );
             let state = Some(pollster::block_on(state));
             self.state = state;
             self.window = Some(window_ptr);
@@ -7,11 +7,47 @@ use serde::{Deserialize, Serialize};
 /// - Serializable for persistence and WASM interop
 /// - Extensible for future configuration options
 /// - Platform-agnostic (no target-specific fields)
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     /// Optional network to visualize. If None, a demo scene is shown.
     #[serde(default)]
     pub network: Option<Network>,
+    /// Optional path to a glTF/GLB file to load in place of the network or demo scene; set by
+    /// `main.rs` when given a `.gltf`/`.glb` argument. Not meaningful on wasm, where there's no
+    /// filesystem to read it from.
+    #[serde(default)]
+    pub gltf_path: Option<String>,
+    /// Optional path to a `.rhai` script defining the scene in place of the network, glTF file,
+    /// or demo scene; set by `main.rs` when given a `.rhai` argument. Not meaningful on wasm,
+    /// where there's no filesystem to read it from.
+    #[serde(default)]
+    pub script_path: Option<String>,
+    /// Optional path to a glTF/GLB file whose meshes/materials are imported into whatever scene
+    /// `network`/`gltf_path`/`script_path` (or the demo scene) produces, rather than replacing
+    /// it - see `gltf_import::import_meshes_into_scene`. Lets a network or script reference an
+    /// imported model by mesh id instead of only the built-in cube/line primitives.
+    #[serde(default)]
+    pub import_gltf_path: Option<String>,
+    /// Requested MSAA sample count for the main color/depth targets. 1 disables multisampling;
+    /// the renderer falls back to the nearest count the adapter actually supports.
+    #[serde(default = "default_msaa_samples")]
+    pub msaa_samples: u32,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            network: None,
+            gltf_path: None,
+            script_path: None,
+            import_gltf_path: None,
+            msaa_samples: default_msaa_samples(),
+        }
+    }
+}
+
+fn default_msaa_samples() -> u32 {
+    4
 }
 
 impl AppConfig {
@@ -19,6 +55,31 @@ impl AppConfig {
     pub fn with_network(network: Network) -> Self {
         Self {
             network: Some(network),
+            ..Self::default()
         }
     }
+
+    /// Create a config that loads the given glTF/GLB file instead of a network or demo scene.
+    pub fn with_gltf_path(gltf_path: String) -> Self {
+        Self {
+            gltf_path: Some(gltf_path),
+            ..Self::default()
+        }
+    }
+
+    /// Create a config that builds its scene from the given `.rhai` script instead of a network,
+    /// glTF file, or the built-in demo scene.
+    pub fn with_script_path(script_path: String) -> Self {
+        Self {
+            script_path: Some(script_path),
+            ..Self::default()
+        }
+    }
+
+    /// Import the given glTF/GLB file's meshes/materials into whichever scene this config
+    /// otherwise produces, rather than replacing it.
+    pub fn with_import_gltf_path(mut self, import_gltf_path: String) -> Self {
+        self.import_gltf_path = Some(import_gltf_path);
+        self
+    }
 }
@@ -0,0 +1,178 @@
+use super::mesh::{Mesh, Vertex};
+use glam::Vec2;
+
+/// End-cap style for the first/last segment of a tessellated polyline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndCap {
+    /// No cap; the ribbon simply stops at the offset vertices.
+    Butt,
+    /// Extend the ribbon by half its width along the end direction.
+    Square,
+    /// A semicircular fan of `segments` triangles.
+    Round { segments: u32 },
+}
+
+/// Ratio of miter length to half-width beyond which a joint falls back to a bevel, matching the
+/// classic stroke-tessellation miter-limit convention (keeps spikes from forming on sharp turns).
+const MITER_LIMIT: f32 = 4.0;
+
+/// Turn a road/corridor centerline into a constant-width ribbon `Mesh` lying flat on the XZ
+/// plane (Y = 0), suitable for `MeshBuffers::from_mesh`. `width` is the `TransportMode`'s lane
+/// width; `cap` controls how the first/last segment ends.
+pub fn tessellate_polyline(points: &[Vec2], width: f32, cap: EndCap) -> Mesh {
+    assert!(points.len() >= 2, "a polyline needs at least two points");
+
+    let half_width = width * 0.5;
+    let segment_count = points.len() - 1;
+
+    let segment_normals: Vec<Vec2> = (0..segment_count)
+        .map(|i| perp(points[i + 1] - points[i]))
+        .collect();
+
+    let mut vertices: Vec<Vertex> = Vec::new();
+    let mut indices: Vec<u32> = Vec::new();
+
+    // One (left, right) pair of offset vertices per input point, plus their world positions so
+    // end caps can extend from them without reading back through `vertices`.
+    let mut pairs: Vec<(u32, u32)> = Vec::with_capacity(points.len());
+    let mut pair_positions: Vec<(Vec2, Vec2)> = Vec::with_capacity(points.len());
+
+    for (i, &point) in points.iter().enumerate() {
+        if i > 0 && i < segment_count {
+            let n0 = segment_normals[i - 1];
+            let n1 = segment_normals[i];
+            let miter = (n0 + n1).normalize_or_zero();
+            let denom = miter.dot(n0);
+
+            if denom.abs() < 1e-4 || (1.0 / denom).abs() > MITER_LIMIT {
+                // Miter would spike on a sharp turn - bevel instead: two vertex pairs at the
+                // joint, one per adjacent segment, stitched together by a small triangle.
+                let left0 = point + n0 * half_width;
+                let right0 = point - n0 * half_width;
+                let left1 = point + n1 * half_width;
+                let right1 = point - n1 * half_width;
+
+                let left0_idx = push_vertex(&mut vertices, left0);
+                let right0_idx = push_vertex(&mut vertices, right0);
+                let left1_idx = push_vertex(&mut vertices, left1);
+                let right1_idx = push_vertex(&mut vertices, right1);
+                indices.extend_from_slice(&[
+                    left0_idx, right0_idx, left1_idx, left1_idx, right0_idx, right1_idx,
+                ]);
+
+                pairs.push((left1_idx, right1_idx));
+                pair_positions.push((left1, right1));
+                continue;
+            }
+
+            let joint_normal = miter * (1.0 / denom);
+            let left = point + joint_normal * half_width;
+            let right = point - joint_normal * half_width;
+            pairs.push((push_vertex(&mut vertices, left), push_vertex(&mut vertices, right)));
+            pair_positions.push((left, right));
+        } else {
+            let joint_normal = if i == 0 {
+                segment_normals[0]
+            } else {
+                segment_normals[segment_count - 1]
+            };
+            let left = point + joint_normal * half_width;
+            let right = point - joint_normal * half_width;
+            pairs.push((push_vertex(&mut vertices, left), push_vertex(&mut vertices, right)));
+            pair_positions.push((left, right));
+        }
+    }
+
+    for i in 0..segment_count {
+        let (left_a, right_a) = pairs[i];
+        let (left_b, right_b) = pairs[i + 1];
+        indices.extend_from_slice(&[left_a, right_a, left_b, left_b, right_a, right_b]);
+    }
+
+    apply_end_cap(
+        &mut vertices,
+        &mut indices,
+        points[1] - points[0],
+        pair_positions[0],
+        half_width,
+        cap,
+    );
+    apply_end_cap(
+        &mut vertices,
+        &mut indices,
+        points[segment_count - 1] - points[segment_count],
+        pair_positions[segment_count],
+        half_width,
+        cap,
+    );
+
+    Mesh {
+        vertices,
+        indices,
+        skin: None,
+    }
+}
+
+/// Unit-length perpendicular of `v` in the XZ plane (rotate 90 degrees).
+fn perp(v: Vec2) -> Vec2 {
+    Vec2::new(-v.y, v.x).normalize_or_zero()
+}
+
+fn push_vertex(vertices: &mut Vec<Vertex>, p: Vec2) -> u32 {
+    let index = vertices.len() as u32;
+    vertices.push(Vertex {
+        position: [p.x, 0.0, p.y],
+        normal: [0.0, 1.0, 0.0],
+    });
+    index
+}
+
+/// Extend the ribbon past `(left, right)` along `-inward_dir` (the direction pointing back into
+/// the polyline, so negating it points outward past the end).
+fn apply_end_cap(
+    vertices: &mut Vec<Vertex>,
+    indices: &mut Vec<u32>,
+    inward_dir: Vec2,
+    (left, right): (Vec2, Vec2),
+    half_width: f32,
+    cap: EndCap,
+) {
+    let outward = (-inward_dir).normalize_or_zero();
+
+    match cap {
+        EndCap::Butt => {}
+        EndCap::Square => {
+            let left_idx = push_vertex(vertices, left);
+            let right_idx = push_vertex(vertices, right);
+            let left_ext_idx = push_vertex(vertices, left + outward * half_width);
+            let right_ext_idx = push_vertex(vertices, right + outward * half_width);
+            indices.extend_from_slice(&[
+                left_idx,
+                right_idx,
+                left_ext_idx,
+                left_ext_idx,
+                right_idx,
+                right_ext_idx,
+            ]);
+        }
+        EndCap::Round { segments } => {
+            let segments = segments.max(1);
+            let center = (left + right) * 0.5;
+            let center_idx = push_vertex(vertices, center);
+
+            let start_angle = (left - center).to_angle();
+            let end_angle = start_angle + std::f32::consts::PI;
+
+            let mut prev_idx = push_vertex(vertices, left);
+            for step in 1..=segments {
+                let t = step as f32 / segments as f32;
+                let angle = start_angle + (end_angle - start_angle) * t;
+                let offset = Vec2::new(angle.cos(), angle.sin()) * half_width;
+                let point = center + offset;
+                let idx = push_vertex(vertices, point);
+                indices.extend_from_slice(&[center_idx, prev_idx, idx]);
+                prev_idx = idx;
+            }
+        }
+    }
+}
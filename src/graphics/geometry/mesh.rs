@@ -0,0 +1,536 @@
+use bytemuck::{Pod, Zeroable};
+use glam::Vec3;
+use std::f32::consts::{FRAC_PI_2, PI};
+use std::fmt;
+use std::path::Path;
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct Vertex {
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+}
+
+impl Vertex {
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32x3,
+                    offset: 0,
+                    shader_location: 0,
+                },
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32x3,
+                    offset: 12,
+                    shader_location: 1,
+                },
+            ],
+        }
+    }
+}
+
+/// Per-vertex skinning data for a joint-animated mesh - the up-to-4 joints influencing this
+/// vertex and their blend weights, parallel to `Mesh::vertices`. A separate per-vertex buffer
+/// from `Vertex` itself (rather than interleaved fields) so the dozens of rigid primitives in
+/// this module don't have to carry dead skinning data around; see `Mesh::skin`/`identity`.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct VertexSkin {
+    pub joint_indices: [u32; 4],
+    pub joint_weights: [f32; 4],
+}
+
+impl VertexSkin {
+    /// One full-weight joint 0, zero everything else - the no-op skin bound for every rigid mesh
+    /// so `MeshBuffers` can always provide this vertex buffer slot, skinned or not (see
+    /// `MeshBuffers::from_mesh`).
+    pub fn identity() -> Self {
+        Self {
+            joint_indices: [0; 4],
+            joint_weights: [1.0, 0.0, 0.0, 0.0],
+        }
+    }
+
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<VertexSkin>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Uint32x4,
+                    offset: 0,
+                    shader_location: 8,
+                },
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32x4,
+                    offset: 16,
+                    shader_location: 9,
+                },
+            ],
+        }
+    }
+}
+
+/// Failure loading a `Mesh` from a Wavefront OBJ file.
+#[derive(Debug)]
+pub enum MeshLoadError {
+    /// `tobj` failed to read or parse the file (or a referenced `.mtl`).
+    Load(tobj::LoadError),
+    /// The file parsed but contained no shapes to build a mesh from.
+    Empty(String),
+}
+
+impl fmt::Display for MeshLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MeshLoadError::Load(err) => write!(f, "failed to load OBJ file: {err}"),
+            MeshLoadError::Empty(path) => write!(f, "OBJ file '{path}' contains no geometry"),
+        }
+    }
+}
+
+impl std::error::Error for MeshLoadError {}
+
+impl From<tobj::LoadError> for MeshLoadError {
+    fn from(err: tobj::LoadError) -> Self {
+        MeshLoadError::Load(err)
+    }
+}
+
+pub struct Mesh {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+    /// One `VertexSkin` per vertex for a joint-animated mesh (see `SceneNode::skeleton_id`), or
+    /// `None` for a rigid mesh. `MeshBuffers::from_mesh` falls back to an all-`identity` buffer
+    /// when this is `None`, so the render pipeline's vertex layout stays the same either way.
+    pub skin: Option<Vec<VertexSkin>>,
+}
+
+impl Mesh {
+    /// Attach per-vertex joint indices/weights, e.g. after loading a glTF `SimpleSkin` mesh.
+    /// Panics if `skin` doesn't have exactly one entry per vertex - a mismatched skin can't be
+    /// sampled meaningfully, so this fails fast at import time rather than at draw time.
+    pub fn with_skin(mut self, skin: Vec<VertexSkin>) -> Self {
+        assert_eq!(
+            skin.len(),
+            self.vertices.len(),
+            "VertexSkin count ({}) must match vertex count ({})",
+            skin.len(),
+            self.vertices.len()
+        );
+        self.skin = Some(skin);
+        self
+    }
+
+    pub fn cube() -> Self {
+        let vertices = vec![
+            // Front face
+            Vertex {
+                position: [-0.5, -0.5, 0.5],
+                normal: [0.0, 0.0, 1.0],
+            },
+            Vertex {
+                position: [0.5, -0.5, 0.5],
+                normal: [0.0, 0.0, 1.0],
+            },
+            Vertex {
+                position: [0.5, 0.5, 0.5],
+                normal: [0.0, 0.0, 1.0],
+            },
+            Vertex {
+                position: [-0.5, 0.5, 0.5],
+                normal: [0.0, 0.0, 1.0],
+            },
+            // Back face
+            Vertex {
+                position: [-0.5, -0.5, -0.5],
+                normal: [0.0, 0.0, -1.0],
+            },
+            Vertex {
+                position: [0.5, -0.5, -0.5],
+                normal: [0.0, 0.0, -1.0],
+            },
+            Vertex {
+                position: [0.5, 0.5, -0.5],
+                normal: [0.0, 0.0, -1.0],
+            },
+            Vertex {
+                position: [-0.5, 0.5, -0.5],
+                normal: [0.0, 0.0, -1.0],
+            },
+            // Top face
+            Vertex {
+                position: [-0.5, 0.5, 0.5],
+                normal: [0.0, 1.0, 0.0],
+            },
+            Vertex {
+                position: [0.5, 0.5, 0.5],
+                normal: [0.0, 1.0, 0.0],
+            },
+            Vertex {
+                position: [0.5, 0.5, -0.5],
+                normal: [0.0, 1.0, 0.0],
+            },
+            Vertex {
+                position: [-0.5, 0.5, -0.5],
+                normal: [0.0, 1.0, 0.0],
+            },
+            // Bottom face
+            Vertex {
+                position: [-0.5, -0.5, 0.5],
+                normal: [0.0, -1.0, 0.0],
+            },
+            Vertex {
+                position: [0.5, -0.5, 0.5],
+                normal: [0.0, -1.0, 0.0],
+            },
+            Vertex {
+                position: [0.5, -0.5, -0.5],
+                normal: [0.0, -1.0, 0.0],
+            },
+            Vertex {
+                position: [-0.5, -0.5, -0.5],
+                normal: [0.0, -1.0, 0.0],
+            },
+            // Right face
+            Vertex {
+                position: [0.5, -0.5, 0.5],
+                normal: [1.0, 0.0, 0.0],
+            },
+            Vertex {
+                position: [0.5, 0.5, 0.5],
+                normal: [1.0, 0.0, 0.0],
+            },
+            Vertex {
+                position: [0.5, 0.5, -0.5],
+                normal: [1.0, 0.0, 0.0],
+            },
+            Vertex {
+                position: [0.5, -0.5, -0.5],
+                normal: [1.0, 0.0, 0.0],
+            },
+            // Left face
+            Vertex {
+                position: [-0.5, -0.5, 0.5],
+                normal: [-1.0, 0.0, 0.0],
+            },
+            Vertex {
+                position: [-0.5, 0.5, 0.5],
+                normal: [-1.0, 0.0, 0.0],
+            },
+            Vertex {
+                position: [-0.5, 0.5, -0.5],
+                normal: [-1.0, 0.0, 0.0],
+            },
+            Vertex {
+                position: [-0.5, -0.5, -0.5],
+                normal: [-1.0, 0.0, 0.0],
+            },
+        ];
+
+        let indices = vec![
+            // Front face
+            2, 1, 0, 3, 2, 0, // Back face
+            6, 4, 5, 7, 4, 6, // Top face
+            10, 9, 8, 11, 10, 8, // Bottom face
+            14, 12, 13, 15, 12, 14, // Right face
+            16, 17, 18, 16, 18, 19, // Left face
+            21, 20, 22, 22, 20, 23,
+        ];
+
+        Self { vertices, indices, skin: None }
+    }
+
+    pub fn quad() -> Self {
+        let vertices = vec![
+            Vertex {
+                position: [-0.5, 0.0, -0.5],
+                normal: [0.0, 1.0, 0.0],
+            },
+            Vertex {
+                position: [0.5, 0.0, -0.5],
+                normal: [0.0, 1.0, 0.0],
+            },
+            Vertex {
+                position: [0.5, 0.0, 0.5],
+                normal: [0.0, 1.0, 0.0],
+            },
+            Vertex {
+                position: [-0.5, 0.0, 0.5],
+                normal: [0.0, 1.0, 0.0],
+            },
+        ];
+
+        let indices = vec![0, 1, 2, 0, 2, 3];
+
+        Self { vertices, indices, skin: None }
+    }
+
+    pub fn line_segment(width: f32) -> Self {
+        // Create a thin quad (2 triangles) lying flat on the XZ plane
+        // This renders as a thin line on the ground
+
+        let vertices = vec![
+            Vertex {
+                position: [-0.5, 0.0, -width / 2.0],
+                normal: [0.0, 1.0, 0.0],
+            }, // Bottom left
+            Vertex {
+                position: [0.5, 0.0, -width / 2.0],
+                normal: [0.0, 1.0, 0.0],
+            }, // Bottom right
+            Vertex {
+                position: [0.5, 0.0, width / 2.0],
+                normal: [0.0, 1.0, 0.0],
+            }, // Top right
+            Vertex {
+                position: [-0.5, 0.0, width / 2.0],
+                normal: [0.0, 1.0, 0.0],
+            }, // Top left
+        ];
+
+        let indices = vec![
+            0, 1, 2, // First triangle
+            0, 2, 3, // Second triangle
+        ];
+
+        Self { vertices, indices, skin: None }
+    }
+
+    /// A unit-diameter UV sphere, ring by ring from pole to pole, `sectors` vertices around each
+    /// ring. Matches `SceneNode::collider`'s `Sphere` variant visually when scaled by `2 * radius`.
+    pub fn uv_sphere(rings: u32, sectors: u32) -> Self {
+        let rings = rings.max(2);
+        let sectors = sectors.max(3);
+        let radius = 0.5;
+
+        let mut vertices = Vec::new();
+        for ring in 0..=rings {
+            let phi = PI * ring as f32 / rings as f32; // 0 at the top pole, PI at the bottom
+            push_ring(&mut vertices, sectors, phi, radius, 0.0);
+        }
+
+        let indices = stitch_rings(rings, sectors);
+
+        Self { vertices, indices, skin: None }
+    }
+
+    /// A capsule: a cylindrical body of `2 * half_height` capped by two hemispheres of `radius`,
+    /// built the same way as `uv_sphere` but with the two hemispheres pulled apart - the middle
+    /// ring of each hemisphere lands exactly on the cylinder's rim, so no special-casing is needed
+    /// for the body.
+    pub fn capsule(radius: f32, half_height: f32, rings: u32, sectors: u32) -> Self {
+        let rings = rings.max(1);
+        let sectors = sectors.max(3);
+
+        let mut vertices = Vec::new();
+        for ring in 0..=rings {
+            let phi = FRAC_PI_2 * ring as f32 / rings as f32; // 0 at top pole, PI/2 at the rim
+            push_ring(&mut vertices, sectors, phi, radius, half_height);
+        }
+        for ring in 0..=rings {
+            let phi = FRAC_PI_2 * (1.0 + ring as f32 / rings as f32); // PI/2 at the rim, PI at bottom
+            push_ring(&mut vertices, sectors, phi, radius, -half_height);
+        }
+
+        let indices = stitch_rings(2 * rings + 1, sectors);
+
+        Self { vertices, indices, skin: None }
+    }
+
+    /// A flat `cols x rows` tessellated plane on the XZ plane, each cell `cell_size` units
+    /// square, centered on the origin - a subdividable version of `quad()` for per-cell picking
+    /// or line-overlay snapping.
+    pub fn grid(cols: u32, rows: u32, cell_size: f32) -> Self {
+        Self::grid_with_heights(cols, rows, cell_size, |_, _| 0.0)
+    }
+
+    /// Same as `grid`, but `height(col, row)` supplies each grid point's Y coordinate (indexed by
+    /// grid position, not world space), for heightmap terrain. Points are shared between
+    /// neighboring cells so a displaced grid still has no seams; normals stay straight up as in
+    /// the flat case, matching this module's other primitives rather than shading the slope.
+    pub fn grid_with_heights(
+        cols: u32,
+        rows: u32,
+        cell_size: f32,
+        mut height: impl FnMut(u32, u32) -> f32,
+    ) -> Self {
+        let cols = cols.max(1);
+        let rows = rows.max(1);
+
+        let half_width = cols as f32 * cell_size * 0.5;
+        let half_depth = rows as f32 * cell_size * 0.5;
+
+        let mut vertices = Vec::new();
+        for row in 0..=rows {
+            for col in 0..=cols {
+                let x = col as f32 * cell_size - half_width;
+                let z = row as f32 * cell_size - half_depth;
+                vertices.push(Vertex {
+                    position: [x, height(col, row), z],
+                    normal: [0.0, 1.0, 0.0],
+                });
+            }
+        }
+
+        let verts_per_row = cols + 1;
+        let mut indices = Vec::new();
+        for row in 0..rows {
+            for col in 0..cols {
+                let a = row * verts_per_row + col;
+                let b = a + verts_per_row;
+                // Same winding as `quad()`'s two triangles: (a, a+1, b+1) then (a, b+1, b).
+                indices.extend_from_slice(&[a, a + 1, b + 1, a, b + 1, b]);
+            }
+        }
+
+        Self { vertices, indices, skin: None }
+    }
+
+    /// Load a mesh from a Wavefront `.obj` file (and its paired `.mtl`, if one is referenced).
+    /// Every shape in the file is merged into one combined `Mesh`, polygons are triangulated, and
+    /// positions/normals are flattened to `tobj`'s single-index form so they line up vertex-for-
+    /// vertex for our interleaved `Vertex` layout. Files that omit normals get smoothed per-vertex
+    /// normals synthesized from the triangles sharing each position.
+    pub fn from_obj(path: impl AsRef<Path>) -> Result<Self, MeshLoadError> {
+        let path = path.as_ref();
+        let (models, _materials) = tobj::load_obj(path, &Self::obj_load_options())?;
+        Self::merge_models(&models, || path.display().to_string())
+    }
+
+    /// Like `from_obj`, but reads from an in-memory `.obj` buffer instead of a filesystem path -
+    /// for embedded assets or anything else that doesn't have the file on disk. `.mtl` references
+    /// can't be resolved from a bare reader, so materials are ignored.
+    pub fn from_obj_reader(reader: &mut impl std::io::BufRead) -> Result<Self, MeshLoadError> {
+        let (models, _materials) =
+            tobj::load_obj_buf(reader, &Self::obj_load_options(), |_mtl_path| {
+                Ok(Default::default())
+            })?;
+        Self::merge_models(&models, || "<reader>".to_string())
+    }
+
+    fn obj_load_options() -> tobj::LoadOptions {
+        tobj::LoadOptions {
+            triangulate: true,
+            single_index: true,
+            ..Default::default()
+        }
+    }
+
+    /// Concatenate every shape's geometry into one `Mesh`, offsetting each shape's indices past
+    /// the vertices already appended.
+    fn merge_models(
+        models: &[tobj::Model],
+        describe: impl FnOnce() -> String,
+    ) -> Result<Self, MeshLoadError> {
+        if models.is_empty() {
+            return Err(MeshLoadError::Empty(describe()));
+        }
+
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+
+        for model in models {
+            let mesh = &model.mesh;
+            let base = vertices.len() as u32;
+
+            let positions: Vec<Vec3> = mesh
+                .positions
+                .chunks_exact(3)
+                .map(|p| Vec3::new(p[0], p[1], p[2]))
+                .collect();
+
+            let normals: Vec<Vec3> = if mesh.normals.is_empty() {
+                synthesize_smooth_normals(&positions, &mesh.indices)
+            } else {
+                mesh.normals
+                    .chunks_exact(3)
+                    .map(|n| Vec3::new(n[0], n[1], n[2]))
+                    .collect()
+            };
+
+            vertices.extend(positions.iter().zip(&normals).map(|(position, normal)| Vertex {
+                position: position.to_array(),
+                normal: normal.to_array(),
+            }));
+            indices.extend(mesh.indices.iter().map(|&index| index + base));
+        }
+
+        Ok(Self { vertices, indices, skin: None })
+    }
+}
+
+/// Append one ring of `sectors + 1` vertices (the last duplicating the first, so UVs/seams don't
+/// wrap) at polar angle `phi` from the +Y pole, on a sphere of `radius` centered `y_offset` above
+/// the origin - shared by `Mesh::uv_sphere` and `Mesh::capsule`.
+fn push_ring(vertices: &mut Vec<Vertex>, sectors: u32, phi: f32, radius: f32, y_offset: f32) {
+    let (sin_phi, cos_phi) = phi.sin_cos();
+
+    for sector in 0..=sectors {
+        let theta = 2.0 * PI * sector as f32 / sectors as f32;
+        let (sin_theta, cos_theta) = theta.sin_cos();
+
+        let position = Vec3::new(
+            radius * sin_phi * cos_theta,
+            y_offset + radius * cos_phi,
+            radius * sin_phi * sin_theta,
+        );
+        // Unit regardless of `radius`, so this also works for the capsule's offset rings.
+        let normal = Vec3::new(cos_theta, 0.0, sin_theta) * sin_phi + Vec3::Y * cos_phi;
+
+        vertices.push(Vertex {
+            position: position.to_array(),
+            normal: normal.to_array(),
+        });
+    }
+}
+
+/// Triangulate `rings` bands between consecutive rows of `sectors + 1` vertices each (as laid
+/// down by `push_ring`) into a quad strip per band, two triangles per quad.
+fn stitch_rings(rings: u32, sectors: u32) -> Vec<u32> {
+    let verts_per_ring = sectors + 1;
+    let mut indices = Vec::new();
+
+    for ring in 0..rings {
+        for sector in 0..sectors {
+            let a = ring * verts_per_ring + sector;
+            let b = a + verts_per_ring;
+            indices.extend_from_slice(&[a, b, a + 1, a + 1, b, b + 1]);
+        }
+    }
+
+    indices
+}
+
+/// Per-vertex normals for a mesh that didn't carry any, computed by accumulating each triangle's
+/// face normal into its three vertices and normalizing - the usual "smoothed" normal synthesis
+/// for meshes where vertices are already shared across faces (as `tobj`'s single-index mode
+/// does).
+fn synthesize_smooth_normals(positions: &[Vec3], indices: &[u32]) -> Vec<Vec3> {
+    let mut normals = vec![Vec3::ZERO; positions.len()];
+
+    for triangle in indices.chunks_exact(3) {
+        let (a, b, c) = (
+            positions[triangle[0] as usize],
+            positions[triangle[1] as usize],
+            positions[triangle[2] as usize],
+        );
+        let face_normal = (b - a).cross(c - a);
+        for &index in triangle {
+            normals[index as usize] += face_normal;
+        }
+    }
+
+    for normal in &mut normals {
+        *normal = if normal.length_squared() > 0.0 {
+            normal.normalize()
+        } else {
+            Vec3::Y
+        };
+    }
+
+    normals
+}
@@ -0,0 +1,5 @@
+mod corridor;
+mod mesh;
+
+pub use corridor::{EndCap, tessellate_polyline};
+pub use mesh::{Mesh, MeshLoadError, Vertex, VertexSkin};
@@ -6,7 +6,9 @@ pub mod ui;
 
 // Re-export public graphics API for state.rs to use
 pub use rendering::{
-    CameraBuffer, GpuContext, InstanceBuffer, InstanceData, LightingBuffer, LightingControls,
-    LightingSettings, MeshBuffers, PickingPass, Pipeline, render_scene,
+    CameraBuffer, Frustum, GpuContext, HDR_COLOR_FORMAT, InstanceBuffer, InstanceData,
+    JointBuffer, LightingBuffer, LightingControls, LightingSettings, MeshBuffers, PickingPass,
+    Pipeline, RenderGraph, RenderTarget, ResourceId, ShadowFilterMode, ShadowMap, ToneMapPass,
+    ToneMappingOperator, UNSKINNED, Viewport, cull_visible_nodes, light_view_proj, render_scene,
 };
-pub use ui::{CameraDebugInfo, RenderStats, EguiIntegration, panels};
+pub use ui::{CameraDebugInfo, EguiIntegration, PreparedUi, RenderStats, panels};
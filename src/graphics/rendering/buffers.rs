@@ -1,12 +1,21 @@
-use crate::graphics::geometry::Mesh;
+use crate::graphics::geometry::{Mesh, VertexSkin};
 use bytemuck::{Pod, Zeroable};
+use glam::Mat4;
 use wgpu::util::DeviceExt;
 
+/// `InstanceData::skin_offset` sentinel for a rigidly-transformed instance - the vertex shader
+/// skips the joint palette lookup entirely rather than reading a (nonexistent) slot 0, same
+/// sentinel convention `PickingPass::CLEAR_SENTINEL` uses for "no node here".
+pub const UNSKINNED: u32 = u32::MAX;
+
 #[repr(C)]
 #[derive(Copy, Clone, Pod, Zeroable)]
 pub struct InstanceData {
     pub matrix: [[f32; 4]; 4],
     pub color: [f32; 4],
+    /// Index of this instance's first joint in the frame's `JointBuffer` palette, or
+    /// `UNSKINNED` for a rigid instance - see `Scene::skinned_joint_matrices`.
+    pub skin_offset: u32,
 }
 
 impl InstanceData {
@@ -42,6 +51,44 @@ impl InstanceData {
                     offset: 64,
                     shader_location: 6,
                 },
+                // Joint palette offset (see `UNSKINNED`)
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Uint32,
+                    offset: 80,
+                    shader_location: 7,
+                },
+            ],
+        }
+    }
+
+    /// Vertex layout for `PickingPass`: the same per-instance buffer and stride as `desc`, but
+    /// omitting the color attribute - the picking shader writes `@builtin(instance_index)` into
+    /// its id target rather than shading with the instance's color.
+    pub fn picking_desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<InstanceData>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32x4,
+                    offset: 0,
+                    shader_location: 2,
+                },
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32x4,
+                    offset: 16,
+                    shader_location: 3,
+                },
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32x4,
+                    offset: 32,
+                    shader_location: 4,
+                },
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32x4,
+                    offset: 48,
+                    shader_location: 5,
+                },
             ],
         }
     }
@@ -51,6 +98,10 @@ pub struct MeshBuffers {
     pub vertex_buffer: wgpu::Buffer,
     pub index_buffer: wgpu::Buffer,
     pub index_count: u32,
+    /// Per-vertex `VertexSkin` data, always present so the main pipeline's vertex layout is the
+    /// same whether or not this mesh is skinned - `mesh.skin`'s data if it has any, else an
+    /// all-`VertexSkin::identity` buffer synthesized here.
+    pub skin_buffer: wgpu::Buffer,
 }
 
 impl MeshBuffers {
@@ -67,43 +118,177 @@ impl MeshBuffers {
             usage: wgpu::BufferUsages::INDEX,
         });
 
+        let skin_buffer = match &mesh.skin {
+            Some(skin) => device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Mesh Skin Buffer"),
+                contents: bytemuck::cast_slice(skin),
+                usage: wgpu::BufferUsages::VERTEX,
+            }),
+            None => device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Mesh Skin Buffer (identity)"),
+                contents: bytemuck::cast_slice(&vec![
+                    VertexSkin::identity();
+                    mesh.vertices.len()
+                ]),
+                usage: wgpu::BufferUsages::VERTEX,
+            }),
+        };
+
         Self {
             vertex_buffer,
             index_buffer,
             index_count: mesh.indices.len() as u32,
+            skin_buffer,
+        }
+    }
+}
+
+/// GPU-side joint matrix palette for skinned meshes: one flat storage buffer holding every
+/// skinned node's joint matrices back to back this frame, indexed per-instance by
+/// `InstanceData::skin_offset` - see `Scene::skinned_joint_matrices`.
+pub struct JointBuffer {
+    pub buffer: wgpu::Buffer,
+    pub bind_group: wgpu::BindGroup,
+    capacity: usize,
+}
+
+/// Smallest palette size, so a scene with no skinned nodes yet doesn't reallocate on the first
+/// one that appears.
+const MIN_JOINT_CAPACITY: usize = 256;
+
+impl JointBuffer {
+    pub fn new(device: &wgpu::Device, bind_group_layout: &wgpu::BindGroupLayout) -> Self {
+        let capacity = MIN_JOINT_CAPACITY;
+        let buffer = Self::create_buffer(device, capacity);
+        let bind_group = Self::create_bind_group(device, bind_group_layout, &buffer);
+
+        Self {
+            buffer,
+            bind_group,
+            capacity,
+        }
+    }
+
+    fn create_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Joint Palette Buffer"),
+            size: (capacity * std::mem::size_of::<[[f32; 4]; 4]>()) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    fn create_bind_group(
+        device: &wgpu::Device,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        buffer: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Joint Palette Bind Group"),
+            layout: bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+        })
+    }
+
+    /// Upload this frame's concatenated joint matrices (see `State::render`), growing - and
+    /// recreating the bind group to point at the new buffer - to the next power-of-two capacity
+    /// whenever it no longer fits, the same strategy as `InstanceBuffer::update`. A no-op when
+    /// `matrices` is empty (no skinned nodes this frame), so the buffer just keeps whatever it
+    /// last held instead of shrinking - harmless, since no instance has a `skin_offset` pointing
+    /// into it when there's nothing to skin.
+    pub fn update(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        matrices: &[[[f32; 4]; 4]],
+    ) {
+        if matrices.is_empty() {
+            return;
+        }
+
+        if matrices.len() > self.capacity {
+            self.capacity = matrices.len().next_power_of_two();
+            self.buffer = Self::create_buffer(device, self.capacity);
+            self.bind_group = Self::create_bind_group(device, bind_group_layout, &self.buffer);
         }
+
+        queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(matrices));
     }
 }
 
 pub struct InstanceBuffer {
     pub buffer: wgpu::Buffer,
     pub capacity: usize,
+    /// Bumped every time `update` reallocates the underlying buffer, so dependent bind groups
+    /// or cached draw state can tell their handle to `buffer` is stale.
+    pub generation: u64,
 }
 
 impl InstanceBuffer {
     pub fn new(device: &wgpu::Device, capacity: usize) -> Self {
-        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        let buffer = Self::create_buffer(device, capacity);
+
+        Self {
+            buffer,
+            capacity,
+            generation: 0,
+        }
+    }
+
+    fn create_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Instance Buffer"),
             size: (capacity * std::mem::size_of::<InstanceData>()) as u64,
             usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: false,
-        });
-
-        Self { buffer, capacity }
+        })
     }
 
-    pub fn update(&self, queue: &wgpu::Queue, data: &[InstanceData]) {
-        assert!(
-            data.len() <= self.capacity,
-            "Instance data exceeds buffer capacity"
-        );
+    /// Upload `data`, growing the buffer to the next power-of-two capacity whenever it no
+    /// longer fits instead of panicking or truncating.
+    pub fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, data: &[InstanceData]) {
+        if data.len() > self.capacity {
+            self.capacity = grow_capacity(data.len());
+            self.buffer = Self::create_buffer(device, self.capacity);
+            self.generation += 1;
+        }
+
         queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(data));
     }
 }
 
+/// Next capacity that fits `needed` instances, growing geometrically (next power of two) rather
+/// than exactly to `needed` so repeated small growth doesn't reallocate every frame.
+fn grow_capacity(needed: usize) -> usize {
+    needed.next_power_of_two()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Growing past the initial capacity must land on a size that fits every instance that
+    /// triggered the growth - `InstanceBuffer::update` relies on this to never truncate `data`.
+    #[test]
+    fn grow_capacity_fits_all_instances_past_initial_capacity() {
+        let initial_capacity = 4;
+        let needed = 10;
+
+        let grown = grow_capacity(needed);
+
+        assert!(grown >= needed, "grown capacity must fit every instance, not just {initial_capacity}");
+        assert_eq!(grown, 16);
+    }
+}
+
 pub struct CameraBuffer {
     pub buffer: wgpu::Buffer,
     pub bind_group: wgpu::BindGroup,
+    inv_view_proj: [[f32; 4]; 4],
 }
 
 impl CameraBuffer {
@@ -127,11 +312,59 @@ impl CameraBuffer {
             }],
         });
 
-        Self { buffer, bind_group }
+        Self {
+            buffer,
+            bind_group,
+            inv_view_proj: Mat4::IDENTITY.to_cols_array_2d(),
+        }
     }
 
-    pub fn update(&self, queue: &wgpu::Queue, view_proj: &[[f32; 4]; 4]) {
+    pub fn update(&mut self, queue: &wgpu::Queue, view_proj: &[[f32; 4]; 4]) {
         queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&[*view_proj]));
+        self.inv_view_proj = Mat4::from_cols_array_2d(view_proj)
+            .inverse()
+            .to_cols_array_2d();
+    }
+
+    /// Inverse of the view-projection matrix uploaded by the last `update`, used to unproject
+    /// picked screen/depth coordinates back into world space.
+    pub fn inverse_view_proj(&self) -> &[[f32; 4]; 4] {
+        &self.inv_view_proj
+    }
+}
+
+/// Upper bound on simultaneous point lights (street lamps, etc); sized to keep `LightingUniform`
+/// a single small std140 block rather than growing the buffer per-scene.
+pub const MAX_POINT_LIGHTS: usize = 16;
+
+/// GPU-side point light: inverse-square falloff `1/(1 + k*d^2)` clamped at `radius`.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PointLightRaw {
+    pub position_radius: [f32; 4], // xyz = world position, w = falloff radius
+    pub color_intensity: [f32; 4], // xyz = color, w = intensity
+}
+
+impl PointLightRaw {
+    pub(crate) fn zero() -> Self {
+        Self {
+            position_radius: [0.0; 4],
+            color_intensity: [0.0; 4],
+        }
+    }
+}
+
+impl From<crate::graphics::rendering::PointLight> for PointLightRaw {
+    fn from(light: crate::graphics::rendering::PointLight) -> Self {
+        Self {
+            position_radius: [
+                light.position.x,
+                light.position.y,
+                light.position.z,
+                light.radius,
+            ],
+            color_intensity: [light.color.x, light.color.y, light.color.z, light.intensity],
+        }
     }
 }
 
@@ -141,6 +374,13 @@ pub struct LightingUniform {
     pub sun_direction: [f32; 4], // xyz = direction, w = intensity
     pub sun_color: [f32; 4],
     pub horizon_color: [f32; 4], // w = ambient height
+    pub point_light_count: [u32; 4], // x = count, yzw = std140 padding
+    pub point_lights: [PointLightRaw; MAX_POINT_LIGHTS],
+    /// The sun's orthographic view-projection, for transforming fragments into light-clip space.
+    pub light_view_proj: [[f32; 4]; 4],
+    /// x = shadow bias, y = filter mode (0 hardware 2x2, 1 PCF, 2 PCSS), z = PCF/blocker-search
+    /// radius, w = PCSS light size.
+    pub shadow_params: [f32; 4],
 }
 
 pub struct LightingBuffer {
@@ -169,7 +409,68 @@ impl LightingBuffer {
         Self { buffer, bind_group }
     }
 
-    pub fn update(&self, queue: &wgpu::Queue, data: &LightingUniform) {
-        queue.write_buffer(&self.buffer, 0, bytemuck::bytes_of(data));
+    /// Upload the sun/ambient terms from `sun` plus `points`, truncating or zero-filling to
+    /// `MAX_POINT_LIGHTS` so callers never need to know the array's fixed size.
+    pub fn update(
+        &self,
+        queue: &wgpu::Queue,
+        sun: &LightingUniform,
+        points: &[crate::graphics::rendering::PointLight],
+    ) {
+        let mut data = *sun;
+
+        let count = points.len().min(MAX_POINT_LIGHTS);
+        data.point_light_count = [count as u32, 0, 0, 0];
+        for (slot, light) in data.point_lights.iter_mut().zip(points.iter()) {
+            *slot = PointLightRaw::from(*light);
+        }
+        for slot in data.point_lights.iter_mut().skip(count) {
+            *slot = PointLightRaw::zero();
+        }
+
+        queue.write_buffer(&self.buffer, 0, bytemuck::bytes_of(&data));
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct ToneMapUniform {
+    pub exposure: f32,
+    pub operator: f32, // 0 = Reinhard, 1 = ACES filmic
+    pub _padding: [f32; 2],
+}
+
+pub struct ToneMapBuffer {
+    pub buffer: wgpu::Buffer,
+}
+
+impl ToneMapBuffer {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Tone Map Buffer"),
+            size: std::mem::size_of::<ToneMapUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self { buffer }
+    }
+
+    pub fn update(
+        &self,
+        queue: &wgpu::Queue,
+        exposure: f32,
+        operator: super::lighting::ToneMappingOperator,
+    ) {
+        let operator_code = match operator {
+            super::lighting::ToneMappingOperator::Reinhard => 0.0,
+            super::lighting::ToneMappingOperator::Aces => 1.0,
+        };
+        let data = ToneMapUniform {
+            exposure,
+            operator: operator_code,
+            _padding: [0.0; 2],
+        };
+        queue.write_buffer(&self.buffer, 0, bytemuck::bytes_of(&data));
     }
 }
@@ -1,16 +1,30 @@
 use std::sync::Arc;
 use winit::window::Window;
 
+/// Format of the offscreen color target `render_scene` draws into; high dynamic range so sun
+/// highlights above 1.0 don't clip before the tone-mapping pass gets a chance to compress them.
+pub const HDR_COLOR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
 pub struct GpuContext {
     pub surface: wgpu::Surface<'static>,
     pub device: wgpu::Device,
     pub queue: wgpu::Queue,
     pub config: wgpu::SurfaceConfiguration,
     pub depth_texture: wgpu::TextureView,
+    /// Resolved sample count for the main color/depth targets; may be lower than what was
+    /// requested if the adapter doesn't support it for the surface format. 1 means no MSAA.
+    pub sample_count: u32,
+    /// Offscreen `HDR_COLOR_FORMAT` target `render_scene` draws into; the tone-mapping pass
+    /// samples this and writes the compressed LDR result to the swapchain.
+    pub hdr_color_view: wgpu::TextureView,
+    /// Multisampled HDR color target the main pass renders into and resolves into
+    /// `hdr_color_view`; `None` when `sample_count` is 1 (resolving a 1x texture into itself is
+    /// pointless).
+    pub msaa_color_view: Option<wgpu::TextureView>,
 }
 
 impl GpuContext {
-    pub async fn new(window: &Arc<Window>) -> Self {
+    pub async fn new(window: &Arc<Window>, requested_sample_count: u32) -> Self {
         let raw_size = window.inner_size();
         // Some web environments report a zero-sized canvas before layout; clamp to at least 1.
         let size = winit::dpi::PhysicalSize::new(raw_size.width.max(1), raw_size.height.max(1));
@@ -42,12 +56,15 @@ impl GpuContext {
         let required_features = wgpu::Features::empty();
 
         let (device, queue) = adapter
-            .request_device(&wgpu::DeviceDescriptor {
-                label: Some("device"),
-                required_features,
-                required_limits: limits,
-                ..Default::default()
-            })
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("device"),
+                    required_features,
+                    required_limits: limits,
+                    ..Default::default()
+                },
+                None,
+            )
             .await
             .expect("Failed to request device");
 
@@ -64,7 +81,12 @@ impl GpuContext {
         };
         surface.configure(&device, &config);
 
-        let depth_texture = Self::create_depth_texture(&device, size.width, size.height);
+        let sample_count = Self::resolve_sample_count(&adapter, config.format, requested_sample_count);
+        let depth_texture =
+            Self::create_depth_texture(&device, size.width, size.height, sample_count);
+        let hdr_color_view = Self::create_hdr_color_target(&device, size.width, size.height);
+        let msaa_color_view =
+            Self::create_msaa_color_target(&device, size.width, size.height, sample_count);
 
         Self {
             surface,
@@ -72,13 +94,47 @@ impl GpuContext {
             queue,
             config,
             depth_texture,
+            sample_count,
+            hdr_color_view,
+            msaa_color_view,
         }
     }
 
+    /// Picks the largest sample count the adapter actually supports for `format` at or below
+    /// `requested`, following the `msaa_sample_count` negotiation ruffle's wgpu backend uses
+    /// (never hard-error on an unsupported count - fall back instead).
+    fn resolve_sample_count(
+        adapter: &wgpu::Adapter,
+        format: wgpu::TextureFormat,
+        requested: u32,
+    ) -> u32 {
+        if requested <= 1 {
+            return 1;
+        }
+
+        let flags = adapter.get_texture_format_features(format).flags;
+        let supported = |count: u32| -> bool {
+            use wgpu::TextureFormatFeatureFlags as Flags;
+            match count {
+                2 => flags.contains(Flags::MULTISAMPLE_X2),
+                4 => flags.contains(Flags::MULTISAMPLE_X4),
+                8 => flags.contains(Flags::MULTISAMPLE_X8),
+                16 => flags.contains(Flags::MULTISAMPLE_X16),
+                _ => false,
+            }
+        };
+
+        [16, 8, 4, 2]
+            .into_iter()
+            .find(|&count| count <= requested && supported(count))
+            .unwrap_or(1)
+    }
+
     pub fn create_depth_texture(
         device: &wgpu::Device,
         width: u32,
         height: u32,
+        sample_count: u32,
     ) -> wgpu::TextureView {
         let texture = device.create_texture(&wgpu::TextureDescriptor {
             label: Some("Depth Texture"),
@@ -88,7 +144,7 @@ impl GpuContext {
                 depth_or_array_layers: 1,
             },
             mip_level_count: 1,
-            sample_count: 1,
+            sample_count,
             dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Depth24Plus,
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
@@ -97,4 +153,59 @@ impl GpuContext {
 
         texture.create_view(&wgpu::TextureViewDescriptor::default())
     }
+
+    /// Multisampled HDR color target to render into when `sample_count > 1`; resolved into
+    /// `hdr_color_view` at the end of the main pass. Returns `None` at 1x.
+    pub fn create_msaa_color_target(
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+        sample_count: u32,
+    ) -> Option<wgpu::TextureView> {
+        if sample_count <= 1 {
+            return None;
+        }
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("MSAA Color Target"),
+            size: wgpu::Extent3d {
+                width: width.max(1),
+                height: height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: HDR_COLOR_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+
+        Some(texture.create_view(&wgpu::TextureViewDescriptor::default()))
+    }
+
+    /// Offscreen, non-multisampled HDR color target that `render_scene` draws into (or that the
+    /// MSAA target resolves into); the tone-mapping pass samples it as a regular texture.
+    pub fn create_hdr_color_target(
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+    ) -> wgpu::TextureView {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("HDR Color Target"),
+            size: wgpu::Extent3d {
+                width: width.max(1),
+                height: height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: HDR_COLOR_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+
+        texture.create_view(&wgpu::TextureViewDescriptor::default())
+    }
 }
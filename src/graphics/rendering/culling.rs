@@ -0,0 +1,42 @@
+use crate::graphics::scene::{AABB, Scene, compute_node_aabb, extract_frustum_planes};
+use glam::{Mat4, Vec4};
+
+/// The six clip-space planes of a camera's view-projection, used to test whether a world-space
+/// AABB is visible before it's submitted for drawing. Each plane is stored as `(a, b, c, d)`
+/// with `a*x + b*y + c*z + d >= 0` on the inside half-space, normalized so `d` is a true signed
+/// distance.
+pub struct Frustum {
+    planes: [Vec4; 6],
+}
+
+impl Frustum {
+    /// Build from an arbitrary view-projection matrix - see `Camera::frustum_planes` for the
+    /// equivalent convenience method when a `Camera` is already on hand.
+    pub fn from_view_proj(view_proj: Mat4) -> Self {
+        Self {
+            planes: extract_frustum_planes(view_proj),
+        }
+    }
+
+    /// An AABB is outside the frustum only if, for some plane, even its furthest corner in the
+    /// plane normal's direction lands on the negative side - see `AABB::in_frustum`.
+    pub fn intersects_aabb(&self, aabb: &AABB) -> bool {
+        aabb.in_frustum(&self.planes)
+    }
+}
+
+/// Node ids that survive testing each node's world-space AABB against `frustum`, in the same
+/// relative order as `scene.nodes`. Preserving order matters: `draw_batched_instances` relies on
+/// same-mesh nodes staying contiguous, and filtering (unlike e.g. a spatial sort) can't break
+/// that invariant since it never reorders.
+pub fn cull_visible_nodes(scene: &Scene, frustum: &Frustum) -> Vec<u32> {
+    (0..scene.nodes.len())
+        .filter(|&idx| {
+            let node = &scene.nodes[idx];
+            let mesh = &scene.meshes[node.mesh_id];
+            let transform = Mat4::from_cols_array_2d(&scene.world_transforms[idx]);
+            frustum.intersects_aabb(&compute_node_aabb(&mesh.vertices, transform))
+        })
+        .map(|idx| idx as u32)
+        .collect()
+}
@@ -0,0 +1,113 @@
+use std::collections::HashSet;
+
+/// A logical resource declared to a [`RenderGraph`] - a name a pass's reads/writes refer to.
+/// The graph only uses these for dependency ordering; the texture/buffer behind a handle is
+/// still owned by whichever struct already creates it (`GpuContext`, `ShadowMap`, `ToneMapPass`),
+/// the same as before the graph existed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ResourceId(usize);
+
+/// One node in the graph: a label for debugging, the resources it reads/writes (used purely to
+/// derive execution order), and the closure that actually records the pass into the encoder.
+struct PassNode<'a> {
+    label: &'static str,
+    reads: Vec<ResourceId>,
+    writes: Vec<ResourceId>,
+    execute: Box<dyn FnOnce(&mut wgpu::CommandEncoder) + 'a>,
+}
+
+/// Declares passes as nodes over named resource handles instead of a hardcoded call sequence,
+/// so composing multi-pass features (shadows feeding the scene pass feeding tone mapping, and
+/// whatever post-process passes come after) is a matter of adding a node rather than editing
+/// `render_scene` itself. Resolves execution order with a topological sort over the
+/// producer/consumer edges between `reads` and `writes`, then runs each node's closure in turn.
+#[derive(Default)]
+pub struct RenderGraph<'a> {
+    resource_count: usize,
+    passes: Vec<PassNode<'a>>,
+}
+
+impl<'a> RenderGraph<'a> {
+    pub fn new() -> Self {
+        Self {
+            resource_count: 0,
+            passes: Vec::new(),
+        }
+    }
+
+    /// Declare a logical resource (a render target, depth buffer, etc.) and get back a handle
+    /// passes can list in their `reads`/`writes`.
+    pub fn resource(&mut self) -> ResourceId {
+        let id = ResourceId(self.resource_count);
+        self.resource_count += 1;
+        id
+    }
+
+    /// Add a pass node. `reads` are resources this pass samples/depends on; `writes` are
+    /// resources this pass renders into. `execute` records the pass's commands into the shared
+    /// encoder once the graph has scheduled it after everything it reads from.
+    pub fn add_pass(
+        &mut self,
+        label: &'static str,
+        reads: &[ResourceId],
+        writes: &[ResourceId],
+        execute: impl FnOnce(&mut wgpu::CommandEncoder) + 'a,
+    ) {
+        self.passes.push(PassNode {
+            label,
+            reads: reads.to_vec(),
+            writes: writes.to_vec(),
+            execute: Box::new(execute),
+        });
+    }
+
+    /// Topologically sort the declared passes (a pass that reads a resource runs after whichever
+    /// pass writes it) and run each one's closure against `encoder` in that order.
+    pub fn execute(self, encoder: &mut wgpu::CommandEncoder) {
+        for pass in Self::topo_sort(self.passes) {
+            (pass.execute)(encoder);
+        }
+    }
+
+    /// Kahn's algorithm over the write -> read dependency edges between passes. Falls back to
+    /// declaration order for passes with no producer/consumer relationship, so independent
+    /// passes (e.g. two shadow casters) keep a stable, predictable order.
+    fn topo_sort(passes: Vec<PassNode<'a>>) -> Vec<PassNode<'a>> {
+        let len = passes.len();
+        let mut remaining: Vec<Option<PassNode<'a>>> = passes.into_iter().map(Some).collect();
+        let mut scheduled = HashSet::with_capacity(len);
+        let mut ordered = Vec::with_capacity(len);
+
+        while ordered.len() < len {
+            let next = remaining.iter().position(|slot| match slot {
+                Some(pass) => pass
+                    .reads
+                    .iter()
+                    .all(|read| scheduled.contains(read) || !Self::is_written_by_any(&remaining, *read)),
+                None => false,
+            });
+
+            match next {
+                Some(index) => {
+                    let pass = remaining[index].take().expect("checked Some above");
+                    scheduled.extend(pass.writes.iter().copied());
+                    ordered.push(pass);
+                }
+                None => {
+                    // A cycle between declared passes - a bug in the caller's graph
+                    // construction, not something the scene can trigger at runtime. Break it by
+                    // running the remaining passes in declaration order rather than panicking
+                    // mid-frame.
+                    ordered.extend(remaining.into_iter().flatten());
+                    break;
+                }
+            }
+        }
+
+        ordered
+    }
+
+    fn is_written_by_any(passes: &[Option<PassNode<'a>>], resource: ResourceId) -> bool {
+        passes.iter().flatten().any(|pass| pass.writes.contains(&resource))
+    }
+}
@@ -0,0 +1,191 @@
+use glam::Vec3;
+
+use super::LightingUniform;
+
+/// A street-lamp-style point light; converted to `PointLightRaw` when uploaded.
+#[derive(Debug, Clone, Copy)]
+pub struct PointLight {
+    pub position: Vec3,
+    pub color: Vec3,
+    pub intensity: f32,
+    pub radius: f32,
+}
+
+/// Shadow-map filtering quality, trading sampling cost for softer penumbrae.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShadowFilterMode {
+    /// Shadows disabled entirely: skip the depth pre-pass and always treat fragments as lit.
+    None,
+    /// A single hardware 2x2 comparison sample (`textureSampleCompare` bilinear PCF).
+    Hardware2x2,
+    /// An N×N grid of comparison samples averaged together, `radius` texels in each direction.
+    Pcf { radius: u32 },
+    /// Percentage-closer soft shadows: a blocker search estimates penumbra width, then PCF is
+    /// resampled at a radius scaled by that estimate.
+    Pcss {
+        blocker_search_radius: u32,
+        light_size: f32,
+    },
+}
+
+impl Default for ShadowFilterMode {
+    fn default() -> Self {
+        Self::Pcf { radius: 2 }
+    }
+}
+
+/// Tone-mapping operator applied to the HDR color target, after `exposure`, before it's
+/// written to the LDR swapchain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToneMappingOperator {
+    /// Simple `c / (1 + c)` per-channel rolloff.
+    Reinhard,
+    /// Narkowicz's filmic approximation of the ACES reference curve; a longer highlight
+    /// shoulder than Reinhard, closer to what film stock and most game engines produce.
+    Aces,
+}
+
+impl Default for ToneMappingOperator {
+    fn default() -> Self {
+        Self::Reinhard
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LightingSettings {
+    pub sun_direction: Vec3,
+    pub sun_color: Vec3,
+    pub sun_intensity: f32,
+    pub horizon_color: Vec3,
+    pub ambient_height: f32,
+    pub shadow_bias: f32,
+    pub filter_mode: ShadowFilterMode,
+}
+
+impl Default for LightingSettings {
+    fn default() -> Self {
+        Self::from(&LightingControls::default())
+    }
+}
+
+impl LightingSettings {
+    /// `light_view_proj` is the sun's orthographic view-projection from `shadow::light_view_proj`,
+    /// computed separately since it needs the scene's bounding box, which this struct doesn't own.
+    pub fn to_uniform(&self, light_view_proj: &[[f32; 4]; 4]) -> LightingUniform {
+        let dir = self.sun_direction.normalize_or_zero();
+
+        let (filter_mode_code, radius_param, light_size_param) = match self.filter_mode {
+            ShadowFilterMode::None => (-1.0, 0.0, 0.0),
+            ShadowFilterMode::Hardware2x2 => (0.0, 0.0, 0.0),
+            ShadowFilterMode::Pcf { radius } => (1.0, radius as f32, 0.0),
+            ShadowFilterMode::Pcss {
+                blocker_search_radius,
+                light_size,
+            } => (2.0, blocker_search_radius as f32, light_size),
+        };
+
+        LightingUniform {
+            sun_direction: [dir.x, dir.y, dir.z, self.sun_intensity],
+            sun_color: [self.sun_color.x, self.sun_color.y, self.sun_color.z, 0.0],
+            horizon_color: [
+                self.horizon_color.x,
+                self.horizon_color.y,
+                self.horizon_color.z,
+                self.ambient_height.max(0.0001),
+            ],
+            point_light_count: [0; 4],
+            point_lights: [super::buffers::PointLightRaw::zero(); super::buffers::MAX_POINT_LIGHTS],
+            light_view_proj: *light_view_proj,
+            shadow_params: [self.shadow_bias, filter_mode_code, radius_param, light_size_param],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LightingControls {
+    pub sun_direction: Vec3,
+    pub sun_color: Vec3,
+    pub sun_intensity: f32,
+    pub horizon_color: Vec3,
+    pub ambient_height: f32,
+    /// Slope-scaled depth bias subtracted from the stored shadow-map depth before comparison;
+    /// trades shadow acne (too low) for peter-panning (too high).
+    pub shadow_bias: f32,
+    /// Width/height of the square shadow-map depth texture.
+    pub shadow_map_resolution: u32,
+    pub filter_mode: ShadowFilterMode,
+    /// Multiplier applied to the HDR color target before tone mapping; raise it to brighten the
+    /// image, lower it to recover detail in blown-out highlights.
+    pub exposure: f32,
+    pub tone_mapping: ToneMappingOperator,
+    /// Point lights (street lamps, etc) added/removed at runtime via `add_point_light`/
+    /// `remove_point_light` - empty by default, so the sun/ambient terms above remain the only
+    /// light source unless a caller opts into more. Uploaded alongside them into the same
+    /// `LightingUniform` (see `LightingBuffer::update`), truncated to `MAX_POINT_LIGHTS`.
+    pub point_lights: Vec<PointLight>,
+}
+
+impl Default for LightingControls {
+    fn default() -> Self {
+        Self {
+            sun_direction: Vec3::new(-0.4, -1.0, -0.3),
+            sun_color: Vec3::new(1.0, 0.7, 0.7),
+            sun_intensity: 1.25,
+            horizon_color: Vec3::new(0.15, 0.2, 0.55),
+            ambient_height: 6.0,
+            shadow_bias: 0.005,
+            shadow_map_resolution: 2048,
+            filter_mode: ShadowFilterMode::default(),
+            exposure: 1.0,
+            tone_mapping: ToneMappingOperator::default(),
+            point_lights: Vec::new(),
+        }
+    }
+}
+
+impl LightingControls {
+    /// Add a point light, returning its index (stable until the next `remove_point_light`).
+    pub fn add_point_light(&mut self, light: PointLight) -> usize {
+        self.point_lights.push(light);
+        self.point_lights.len() - 1
+    }
+
+    /// Remove the point light at `index`, if it exists.
+    pub fn remove_point_light(&mut self, index: usize) {
+        if index < self.point_lights.len() {
+            self.point_lights.remove(index);
+        }
+    }
+}
+
+impl From<&LightingControls> for LightingSettings {
+    fn from(value: &LightingControls) -> Self {
+        Self {
+            sun_direction: value.sun_direction.normalize_or_zero(),
+            sun_color: value.sun_color,
+            sun_intensity: value.sun_intensity,
+            horizon_color: value.horizon_color,
+            ambient_height: value.ambient_height,
+            shadow_bias: value.shadow_bias,
+            filter_mode: value.filter_mode,
+        }
+    }
+}
+
+impl From<&LightingSettings> for LightingControls {
+    fn from(value: &LightingSettings) -> Self {
+        Self {
+            sun_direction: value.sun_direction,
+            sun_color: value.sun_color,
+            sun_intensity: value.sun_intensity,
+            horizon_color: value.horizon_color,
+            ambient_height: value.ambient_height,
+            shadow_bias: value.shadow_bias,
+            shadow_map_resolution: LightingControls::default().shadow_map_resolution,
+            filter_mode: value.filter_mode,
+            exposure: LightingControls::default().exposure,
+            tone_mapping: LightingControls::default().tone_mapping,
+            point_lights: LightingControls::default().point_lights,
+        }
+    }
+}
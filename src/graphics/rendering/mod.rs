@@ -1,15 +1,26 @@
 mod buffers;
 mod context;
+mod culling;
+mod graph;
 mod lighting;
 mod picking;
 mod pipeline;
 mod renderer;
+mod shadow;
+mod target;
+mod tonemap;
 
 pub use buffers::{
-    CameraBuffer, InstanceBuffer, InstanceData, LightingBuffer, LightingUniform, MeshBuffers,
+    CameraBuffer, InstanceBuffer, InstanceData, JointBuffer, LightingBuffer, LightingUniform,
+    MeshBuffers, UNSKINNED,
 };
-pub use context::GpuContext;
-pub use lighting::{LightingControls, LightingSettings};
+pub use context::{GpuContext, HDR_COLOR_FORMAT};
+pub use culling::{Frustum, cull_visible_nodes};
+pub use graph::{RenderGraph, ResourceId};
+pub use lighting::{LightingControls, LightingSettings, PointLight, ShadowFilterMode, ToneMappingOperator};
 pub use picking::PickingPass;
 pub use pipeline::Pipeline;
 pub use renderer::render_scene;
+pub use shadow::{ShadowMap, light_view_proj};
+pub use target::{OffscreenTarget, RenderTarget, Viewport};
+pub use tonemap::ToneMapPass;
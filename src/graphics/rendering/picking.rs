@@ -3,8 +3,12 @@ use super::renderer::draw_batched_instances;
 use crate::graphics::geometry::Vertex;
 use crate::graphics::scene::Scene;
 use crate::graphics::shaders;
+use std::collections::HashSet;
 use std::sync::mpsc;
 
+/// Sentinel written by the clear color and left untouched by any instance - never a valid pick.
+const CLEAR_SENTINEL: u32 = u32::MAX;
+
 pub struct PickingPass {
     pipeline: wgpu::RenderPipeline,
     debug_pipeline: Option<DebugOverlayPipeline>,
@@ -12,7 +16,14 @@ pub struct PickingPass {
     picking_texture: wgpu::Texture,
     picking_view: wgpu::TextureView,
     readback_buffer: wgpu::Buffer,
+    // Owned depth attachment for the picking pass; `Depth32Float` so it can be copied to a
+    // readback buffer (the main pass's `Depth24Plus` target cannot).
+    depth_texture: wgpu::Texture,
+    depth_view: wgpu::TextureView,
+    depth_readback_buffer: wgpu::Buffer,
     pending_pick: Option<PendingPick>,
+    region_readback_buffer: Option<wgpu::Buffer>,
+    pending_region: Option<PendingRegion>,
     size: (u32, u32),
 }
 
@@ -27,6 +38,43 @@ struct PendingPick {
     frame_submitted: bool,
     map_requested: bool,
     receiver: Option<std::sync::mpsc::Receiver<Result<(), wgpu::BufferAsyncError>>>,
+    id_ready: bool,
+    depth_map_requested: bool,
+    depth_receiver: Option<std::sync::mpsc::Receiver<Result<(), wgpu::BufferAsyncError>>>,
+    depth_ready: bool,
+}
+
+struct PendingRegion {
+    origin: (u32, u32),
+    size: (u32, u32),
+    bytes_per_row: u32,
+    frame_submitted: bool,
+    map_requested: bool,
+    receiver: Option<std::sync::mpsc::Receiver<Result<(), wgpu::BufferAsyncError>>>,
+}
+
+/// Round `value` up to the next multiple of `alignment`.
+fn align_up(value: u32, alignment: u32) -> u32 {
+    (value + alignment - 1) / alignment * alignment
+}
+
+/// Unproject a picked pixel + depth back into world space via the inverse view-projection.
+fn unproject(
+    pixel_x: u32,
+    pixel_y: u32,
+    depth: f32,
+    width: u32,
+    height: u32,
+    inv_view_proj: &[[f32; 4]; 4],
+) -> [f32; 3] {
+    let ndc_x = (2.0 * pixel_x as f32 / width as f32) - 1.0;
+    let ndc_y = 1.0 - (2.0 * pixel_y as f32 / height as f32);
+
+    let inv = glam::Mat4::from_cols_array_2d(inv_view_proj);
+    let clip = glam::Vec4::new(ndc_x, ndc_y, depth, 1.0);
+    let world = inv * clip;
+
+    (world.truncate() / world.w).to_array()
 }
 
 impl PickingPass {
@@ -68,6 +116,15 @@ impl PickingPass {
             mapped_at_creation: false,
         });
 
+        let (depth_texture, depth_view) = Self::create_depth_target(device, width, height);
+
+        let depth_readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Picking Depth Readback Buffer"),
+            size: wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
         // Create picking shader module
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Picking Shader"),
@@ -103,7 +160,7 @@ impl PickingPass {
                 conservative: false,
             },
             depth_stencil: Some(wgpu::DepthStencilState {
-                format: wgpu::TextureFormat::Depth24Plus,
+                format: wgpu::TextureFormat::Depth32Float,
                 depth_write_enabled: true,
                 depth_compare: wgpu::CompareFunction::Less,
                 stencil: wgpu::StencilState::default(),
@@ -129,11 +186,39 @@ impl PickingPass {
             picking_texture,
             picking_view,
             readback_buffer,
+            depth_texture,
+            depth_view,
+            depth_readback_buffer,
             pending_pick: None,
+            region_readback_buffer: None,
+            pending_region: None,
             size: (width, height),
         }
     }
 
+    fn create_depth_target(
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+    ) -> (wgpu::Texture, wgpu::TextureView) {
+        let depth_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Picking Depth Texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth32Float,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        (depth_texture, depth_view)
+    }
+
     pub fn request_pick(&mut self, window_x: u32, window_y: u32, scale_factor: f64) {
         // On native, winit's WindowEvent coords are already physical; on web, apply the scale factor.
         let factor = if cfg!(target_arch = "wasm32") {
@@ -156,6 +241,47 @@ impl PickingPass {
             frame_submitted: false,
             map_requested: false,
             receiver: None,
+            id_ready: false,
+            depth_map_requested: false,
+            depth_receiver: None,
+            depth_ready: false,
+        });
+    }
+
+    /// Request a rectangular (marquee/box-select) pick covering `(x0, y0)..(x1, y1)` in window
+    /// coordinates. The rectangle is clamped to the picking texture bounds; corners may be given
+    /// in either order. Resolve with `poll_result_region`.
+    pub fn request_pick_region(&mut self, x0: u32, y0: u32, x1: u32, y1: u32, scale_factor: f64) {
+        let factor = if cfg!(target_arch = "wasm32") {
+            scale_factor
+        } else {
+            1.0
+        };
+
+        let to_physical = |v: u32| (v as f64 * factor) as u32;
+        let (px0, px1) = {
+            let (a, b) = (to_physical(x0), to_physical(x1));
+            (a.min(b), a.max(b))
+        };
+        let (py0, py1) = {
+            let (a, b) = (to_physical(y0), to_physical(y1));
+            (a.min(b), a.max(b))
+        };
+
+        let origin_x = px0.min(self.size.0.saturating_sub(1));
+        let origin_y = py0.min(self.size.1.saturating_sub(1));
+        let width = (px1 - px0 + 1).min(self.size.0 - origin_x);
+        let height = (py1 - py0 + 1).min(self.size.1 - origin_y);
+
+        let bytes_per_row = align_up(width * 4, wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+
+        self.pending_region = Some(PendingRegion {
+            origin: (origin_x, origin_y),
+            size: (width, height),
+            bytes_per_row,
+            frame_submitted: false,
+            map_requested: false,
+            receiver: None,
         });
     }
 
@@ -165,10 +291,15 @@ impl PickingPass {
             .map_or(false, |p| !p.frame_submitted)
     }
 
+    pub fn should_execute_region(&self) -> bool {
+        self.pending_region
+            .as_ref()
+            .map_or(false, |p| !p.frame_submitted)
+    }
+
     pub fn execute_pick(
         &mut self,
         encoder: &mut wgpu::CommandEncoder,
-        depth_view: &wgpu::TextureView,
         mesh_buffers: &[MeshBuffers],
         instance_buffer: &InstanceBuffer,
         camera_buffer: &CameraBuffer,
@@ -196,13 +327,14 @@ impl PickingPass {
                     }),
                     store: wgpu::StoreOp::Store,
                 },
-                depth_slice: None,
             })],
             depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                view: depth_view,
+                view: &self.depth_view,
                 depth_ops: Some(wgpu::Operations {
                     load: wgpu::LoadOp::Clear(1.0),
-                    store: wgpu::StoreOp::Discard,
+                    // Keep depth so the picked texel's depth can be unprojected back to a
+                    // world-space position (see `poll_result`).
+                    store: wgpu::StoreOp::Store,
                 }),
                 stencil_ops: None,
             }),
@@ -214,10 +346,40 @@ impl PickingPass {
         render_pass.set_bind_group(0, &camera_buffer.bind_group, &[]);
         render_pass.set_vertex_buffer(1, instance_buffer.buffer.slice(..));
 
-        draw_batched_instances(&mut render_pass, mesh_buffers, scene);
+        // Picking must be able to hit any node under the cursor, so draw the full scene here
+        // rather than the main pass's frustum-culled subset (see `State::render`).
+        let all_nodes: Vec<u32> = (0..scene.nodes.len() as u32).collect();
+        draw_batched_instances(&mut render_pass, mesh_buffers, scene, &all_nodes);
 
         drop(render_pass);
 
+        // Copy the target pixel's depth into the depth readback buffer
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: &self.depth_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: pixel_x,
+                    y: pixel_y,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::DepthOnly,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &self.depth_readback_buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+                    rows_per_image: Some(1),
+                },
+            },
+            wgpu::Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+        );
+
         // Copy the target pixel into the readback buffer for CPU access
         encoder.copy_texture_to_buffer(
             wgpu::TexelCopyTextureInfo {
@@ -251,7 +413,118 @@ impl PickingPass {
         }
     }
 
-    pub fn poll_result(&mut self, device: &wgpu::Device) -> Option<u32> {
+    /// Render the pending region pick and copy the rectangle into a region readback buffer
+    /// sized to its padded row stride. Resolve with `poll_result_region`.
+    pub fn execute_pick_region(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        mesh_buffers: &[MeshBuffers],
+        instance_buffer: &InstanceBuffer,
+        camera_buffer: &CameraBuffer,
+        scene: &Scene,
+        device: &wgpu::Device,
+    ) {
+        let pending = match &self.pending_region {
+            Some(p) if !p.frame_submitted => p,
+            _ => return,
+        };
+
+        let (origin_x, origin_y) = pending.origin;
+        let (width, height) = pending.size;
+        let bytes_per_row = pending.bytes_per_row;
+
+        // (Re)allocate the region readback buffer to fit this rectangle
+        let required_size = (bytes_per_row as u64) * (height as u64);
+        let needs_alloc = self
+            .region_readback_buffer
+            .as_ref()
+            .map_or(true, |b| b.size() < required_size);
+        if needs_alloc {
+            self.region_readback_buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Picking Region Readback Buffer"),
+                size: required_size,
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            }));
+        }
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Picking Region Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &self.picking_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color {
+                        r: u32::MAX as f64,
+                        g: 0.0,
+                        b: 0.0,
+                        a: 0.0,
+                    }),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &self.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Discard,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &camera_buffer.bind_group, &[]);
+        render_pass.set_vertex_buffer(1, instance_buffer.buffer.slice(..));
+
+        // Picking must be able to hit any node under the cursor, so draw the full scene here
+        // rather than the main pass's frustum-culled subset (see `State::render`).
+        let all_nodes: Vec<u32> = (0..scene.nodes.len() as u32).collect();
+        draw_batched_instances(&mut render_pass, mesh_buffers, scene, &all_nodes);
+
+        drop(render_pass);
+
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: &self.picking_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: origin_x,
+                    y: origin_y,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: self.region_readback_buffer.as_ref().unwrap(),
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        if let Some(pending) = self.pending_region.as_mut() {
+            pending.frame_submitted = true;
+        }
+    }
+
+    /// Resolve a pending single-pixel pick into its instance id and unprojected world-space
+    /// position, using `inv_view_proj` (see `CameraBuffer::inverse_view_proj`) to undo the
+    /// camera's view-projection. Returns `None` until both the id and depth readbacks complete.
+    pub fn poll_result(
+        &mut self,
+        device: &wgpu::Device,
+        inv_view_proj: &[[f32; 4]; 4],
+    ) -> Option<(u32, [f32; 3])> {
         let pending = self.pending_pick.as_mut()?;
         if !pending.frame_submitted {
             return None;
@@ -265,12 +538,106 @@ impl PickingPass {
             });
             pending.map_requested = true;
             pending.receiver = Some(receiver);
+        }
+
+        if !pending.depth_map_requested {
+            let depth_slice = self.depth_readback_buffer.slice(..);
+            let (sender, receiver) = mpsc::channel();
+            depth_slice.map_async(wgpu::MapMode::Read, move |result| {
+                let _ = sender.send(result);
+            });
+            pending.depth_map_requested = true;
+            pending.depth_receiver = Some(receiver);
+            return None;
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let _ = device.poll(wgpu::Maintain::Poll);
+        }
+        #[cfg(target_arch = "wasm32")]
+        let _ = device;
+
+        if !pending.id_ready {
+            match pending.receiver.as_ref()?.try_recv() {
+                Ok(_) => pending.id_ready = true,
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.pending_pick = None;
+                    return None;
+                }
+            }
+        }
+        if !pending.depth_ready {
+            match pending.depth_receiver.as_ref()?.try_recv() {
+                Ok(_) => pending.depth_ready = true,
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.pending_pick = None;
+                    return None;
+                }
+            }
+        }
+        if !pending.id_ready || !pending.depth_ready {
+            return None;
+        }
+
+        let (pixel_x, pixel_y) = pending.pixel_coords;
+        let (width, height) = self.size;
+        self.pending_pick = None;
+
+        let id_buffer_slice = self.readback_buffer.slice(..);
+        let id_data = id_buffer_slice.get_mapped_range();
+        let instance_id = u32::from_le_bytes([id_data[0], id_data[1], id_data[2], id_data[3]]);
+        drop(id_data);
+        self.readback_buffer.unmap();
+
+        if instance_id == CLEAR_SENTINEL {
+            self.depth_readback_buffer.unmap();
+            return None;
+        }
+
+        let depth_buffer_slice = self.depth_readback_buffer.slice(..);
+        let depth_data = depth_buffer_slice.get_mapped_range();
+        let depth = f32::from_le_bytes([
+            depth_data[0],
+            depth_data[1],
+            depth_data[2],
+            depth_data[3],
+        ]);
+        drop(depth_data);
+        self.depth_readback_buffer.unmap();
+
+        let world_pos = unproject(pixel_x, pixel_y, depth, width, height, inv_view_proj);
+
+        Some((instance_id, world_pos))
+    }
+
+    /// Resolve a pending region pick into the set of unique instance ids covered by the
+    /// rectangle, ignoring the `u32::MAX` clear sentinel. Returns `None` until the readback
+    /// completes.
+    pub fn poll_result_region(&mut self, device: &wgpu::Device) -> Option<HashSet<u32>> {
+        let pending = self.pending_region.as_mut()?;
+        if !pending.frame_submitted {
+            return None;
+        }
+
+        let buffer = self.region_readback_buffer.as_ref()?;
+
+        if !pending.map_requested {
+            let buffer_slice = buffer.slice(..);
+            let (sender, receiver) = mpsc::channel();
+            buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+                let _ = sender.send(result);
+            });
+            pending.map_requested = true;
+            pending.receiver = Some(receiver);
             return None;
         }
 
         #[cfg(not(target_arch = "wasm32"))]
         {
-            let _ = device.poll(wgpu::PollType::Poll);
+            let _ = device.poll(wgpu::Maintain::Poll);
         }
         #[cfg(target_arch = "wasm32")]
         let _ = device;
@@ -278,22 +645,42 @@ impl PickingPass {
         let receiver = pending.receiver.as_ref()?;
         match receiver.try_recv() {
             Ok(Ok(())) => {
-                let buffer_slice = self.readback_buffer.slice(..);
+                let (width, height) = pending.size;
+                let bytes_per_row = pending.bytes_per_row;
+
+                let buffer_slice = buffer.slice(..);
                 let data = buffer_slice.get_mapped_range();
-                let value = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+
+                let mut ids = HashSet::new();
+                for row in 0..height {
+                    let row_start = (row * bytes_per_row) as usize;
+                    for col in 0..width {
+                        let offset = row_start + (col * 4) as usize;
+                        let value = u32::from_le_bytes([
+                            data[offset],
+                            data[offset + 1],
+                            data[offset + 2],
+                            data[offset + 3],
+                        ]);
+                        if value != CLEAR_SENTINEL {
+                            ids.insert(value);
+                        }
+                    }
+                }
+
                 drop(data);
-                self.readback_buffer.unmap();
-                self.pending_pick = None;
-                Some(value)
+                buffer.unmap();
+                self.pending_region = None;
+                Some(ids)
             }
             Ok(Err(_)) => {
-                self.readback_buffer.unmap();
-                self.pending_pick = None;
+                buffer.unmap();
+                self.pending_region = None;
                 None
             }
             Err(std::sync::mpsc::TryRecvError::Empty) => None,
             Err(std::sync::mpsc::TryRecvError::Disconnected) => {
-                self.pending_pick = None;
+                self.pending_region = None;
                 None
             }
         }
@@ -302,6 +689,7 @@ impl PickingPass {
     pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
         // Cancel any pending picks
         self.pending_pick = None;
+        self.pending_region = None;
 
         let width = width.max(1);
         let height = height.max(1);
@@ -327,6 +715,11 @@ impl PickingPass {
         self.picking_view = self
             .picking_texture
             .create_view(&wgpu::TextureViewDescriptor::default());
+
+        let (depth_texture, depth_view) = Self::create_depth_target(device, width, height);
+        self.depth_texture = depth_texture;
+        self.depth_view = depth_view;
+
         self.size = (width, height);
     }
 
@@ -361,7 +754,6 @@ impl PickingPass {
                     load: wgpu::LoadOp::Load,
                     store: wgpu::StoreOp::Store,
                 },
-                depth_slice: None,
             })],
             depth_stencil_attachment: None,
             timestamp_writes: None,
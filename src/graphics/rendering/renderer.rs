@@ -1,43 +1,68 @@
 use super::buffers::{CameraBuffer, InstanceBuffer, LightingBuffer, LightingUniform, MeshBuffers};
+use super::lighting::PointLight;
+use super::target::RenderTarget;
 use crate::graphics::scene::Scene;
 
+/// Render `scene` from `camera_buffer`'s point of view into `target`. Callers that want several
+/// cameras per frame (split-screen, a mirror into an `OffscreenTarget`) just call this once per
+/// camera with a different `target`/`camera_buffer` pair - a later call with `target.clear` unset
+/// loads rather than clobbers whatever an earlier call already drew into a shared attachment.
 pub fn render_scene(
     encoder: &mut wgpu::CommandEncoder,
-    view: &wgpu::TextureView,
-    depth_view: &wgpu::TextureView,
+    target: &RenderTarget,
     render_pipeline: &wgpu::RenderPipeline,
     mesh_buffers: &[MeshBuffers],
     instance_buffer: &InstanceBuffer,
     camera_buffer: &CameraBuffer,
     lighting_buffer: &LightingBuffer,
+    shadow_bind_group: &wgpu::BindGroup,
+    joint_bind_group: &wgpu::BindGroup,
     queue: &wgpu::Queue,
     scene: &Scene,
     lighting: &LightingUniform,
+    point_lights: &[PointLight],
+    visible: &[u32],
 ) {
-    // Update lighting uniform
-    lighting_buffer.update(queue, lighting);
+    // Update lighting uniform (sun/ambient terms plus any point lights, e.g. street lamps)
+    lighting_buffer.update(queue, lighting, point_lights);
+
+    // When multisampling, render into the MSAA target and resolve into the final view; the MSAA
+    // attachment itself doesn't need to be stored once resolved.
+    let (color_view, resolve_target, color_store_op) = match target.msaa_view {
+        Some(msaa) => (msaa, Some(target.view), wgpu::StoreOp::Discard),
+        None => (target.view, None, wgpu::StoreOp::Store),
+    };
+    let color_load_op = if target.clear {
+        wgpu::LoadOp::Clear(wgpu::Color {
+            r: 0.1,
+            g: 0.1,
+            b: 0.1,
+            a: 1.0,
+        })
+    } else {
+        wgpu::LoadOp::Load
+    };
+    let depth_load_op = if target.clear {
+        wgpu::LoadOp::Clear(1.0)
+    } else {
+        wgpu::LoadOp::Load
+    };
 
     // Begin render pass
     let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
         label: Some("Scene Render Pass"),
         color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-            view,
-            resolve_target: None,
+            view: color_view,
+            resolve_target,
             ops: wgpu::Operations {
-                load: wgpu::LoadOp::Clear(wgpu::Color {
-                    r: 0.1,
-                    g: 0.1,
-                    b: 0.1,
-                    a: 1.0,
-                }),
-                store: wgpu::StoreOp::Store,
+                load: color_load_op,
+                store: color_store_op,
             },
-            depth_slice: None,
         })],
         depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-            view: depth_view,
+            view: target.depth_view,
             depth_ops: Some(wgpu::Operations {
-                load: wgpu::LoadOp::Clear(1.0),
+                load: depth_load_op,
                 store: wgpu::StoreOp::Discard,
             }),
             stencil_ops: None,
@@ -46,29 +71,50 @@ pub fn render_scene(
         occlusion_query_set: None,
     });
 
+    let viewport = target.viewport;
+    render_pass.set_viewport(viewport.x, viewport.y, viewport.width, viewport.height, 0.0, 1.0);
+
     render_pass.set_pipeline(render_pipeline);
     render_pass.set_bind_group(0, &camera_buffer.bind_group, &[]);
     render_pass.set_bind_group(1, &lighting_buffer.bind_group, &[]);
+    render_pass.set_bind_group(2, shadow_bind_group, &[]);
+    render_pass.set_bind_group(3, joint_bind_group, &[]);
     render_pass.set_vertex_buffer(1, instance_buffer.buffer.slice(..));
 
-    draw_batched_instances(&mut render_pass, mesh_buffers, scene);
+    draw_batched_instances(&mut render_pass, mesh_buffers, scene, visible);
 }
 
+/// Batch instanced draws by `(mesh_id, material_id)`: a material never needs its own bind
+/// group or pipeline switch since its color is baked into each instance's `InstanceData`, so
+/// grouping by `mesh_id` alone already yields one draw call per distinct mesh/material pair as
+/// long as instances sharing a mesh are contiguous in `scene.nodes` - true for `network_to_scene`,
+/// which pushes every pillar/mode-layer node (cube mesh) before any edge (line mesh).
+///
+/// `visible` is the list of node ids to draw, in `scene.nodes` order, and doubles as the index
+/// into `instance_buffer` - callers upload instance data for exactly these nodes, in this same
+/// order (see frustum culling in `State::render`), so instance range `i..i+n` here means slots
+/// `i..i+n` of that buffer, not of `scene.nodes`.
 pub(crate) fn draw_batched_instances<'a>(
     render_pass: &mut wgpu::RenderPass<'a>,
     mesh_buffers: &'a [MeshBuffers],
     scene: &'a Scene,
+    visible: &[u32],
 ) {
     let mut current_mesh: Option<usize> = None;
     let mut instance_start = 0;
     let mut instance_count = 0;
 
-    for (i, node) in scene.nodes.iter().enumerate() {
+    for (i, node) in visible
+        .iter()
+        .map(|&node_id| &scene.nodes[node_id as usize])
+        .enumerate()
+    {
         if current_mesh != Some(node.mesh_id) {
             // Draw previous batch if any
             if let Some(mesh_id) = current_mesh {
                 let mesh_buf = &mesh_buffers[mesh_id];
                 render_pass.set_vertex_buffer(0, mesh_buf.vertex_buffer.slice(..));
+                render_pass.set_vertex_buffer(2, mesh_buf.skin_buffer.slice(..));
                 render_pass
                     .set_index_buffer(mesh_buf.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
                 render_pass.draw_indexed(
@@ -91,6 +137,7 @@ pub(crate) fn draw_batched_instances<'a>(
     if let Some(mesh_id) = current_mesh {
         let mesh_buf = &mesh_buffers[mesh_id];
         render_pass.set_vertex_buffer(0, mesh_buf.vertex_buffer.slice(..));
+        render_pass.set_vertex_buffer(2, mesh_buf.skin_buffer.slice(..));
         render_pass.set_index_buffer(mesh_buf.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
         render_pass.draw_indexed(
             0..mesh_buf.index_count,
@@ -0,0 +1,222 @@
+use super::buffers::{CameraBuffer, InstanceBuffer, InstanceData, MeshBuffers};
+use crate::graphics::geometry::Vertex;
+use crate::graphics::scene::{AABB, Scene};
+use crate::graphics::shaders;
+use glam::{Mat4, Vec3};
+
+/// Build the sun's orthographic view-projection, tightly bounding `bounds` and looking down
+/// `sun_direction` from outside the scene - the frustum used to render the shadow map.
+pub fn light_view_proj(sun_direction: Vec3, bounds: &AABB) -> Mat4 {
+    let center = (bounds.min() + bounds.max()) * 0.5;
+    let radius = (bounds.max() - bounds.min()).length() * 0.5;
+    let radius = radius.max(1.0);
+
+    let direction = {
+        let normalized = sun_direction.normalize_or_zero();
+        if normalized == Vec3::ZERO {
+            Vec3::new(0.0, -1.0, 0.0)
+        } else {
+            normalized
+        }
+    };
+    let up = if direction.abs().dot(Vec3::Y) > 0.99 {
+        Vec3::X
+    } else {
+        Vec3::Y
+    };
+
+    let eye = center - direction * radius * 2.0;
+    let view = Mat4::look_at_rh(eye, center, up);
+    let proj = Mat4::orthographic_rh(-radius, radius, -radius, radius, 0.0, radius * 4.0);
+
+    proj * view
+}
+
+/// Depth-only render target + pipeline for directional shadow mapping.
+pub struct ShadowMap {
+    pipeline: wgpu::RenderPipeline,
+    #[allow(dead_code)]
+    shader: wgpu::ShaderModule,
+    depth_texture: wgpu::Texture,
+    pub depth_view: wgpu::TextureView,
+    pub sampler: wgpu::Sampler,
+    pub light_buffer: CameraBuffer,
+    resolution: u32,
+}
+
+impl ShadowMap {
+    pub fn new(
+        device: &wgpu::Device,
+        light_bind_group_layout: &wgpu::BindGroupLayout,
+        resolution: u32,
+    ) -> Self {
+        let (depth_texture, depth_view) = Self::create_depth_target(device, resolution);
+
+        // Comparison sampler: the shader samples it with `textureSampleCompare`, which does the
+        // hardware 2x2 PCF comparison itself; manual PCF/PCSS kernels take multiple such samples.
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Shadow Comparison Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            compare: Some(wgpu::CompareFunction::LessEqual),
+            ..Default::default()
+        });
+
+        let light_buffer = CameraBuffer::new(device, light_bind_group_layout);
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Shadow Shader"),
+            source: wgpu::ShaderSource::Wgsl(shaders::shadow_shader_source().into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Shadow Pipeline Layout"),
+            bind_group_layouts: &[light_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Shadow Pipeline"),
+            layout: Some(&pipeline_layout),
+            multiview: None,
+            cache: None,
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[Vertex::desc(), InstanceData::desc()],
+            },
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                // Cull front faces (instead of back) when rendering depth from the light's view -
+                // the classic trick that avoids peter-panning without needing a large bias.
+                cull_mode: Some(wgpu::Face::Front),
+                unclipped_depth: false,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            fragment: None,
+        });
+
+        Self {
+            pipeline,
+            shader,
+            depth_texture,
+            depth_view,
+            sampler,
+            light_buffer,
+            resolution,
+        }
+    }
+
+    fn create_depth_target(
+        device: &wgpu::Device,
+        resolution: u32,
+    ) -> (wgpu::Texture, wgpu::TextureView) {
+        let resolution = resolution.max(1);
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Shadow Map Depth Texture"),
+            size: wgpu::Extent3d {
+                width: resolution,
+                height: resolution,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth32Float,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        (texture, view)
+    }
+
+    /// Build the group-2 bind group the main pass samples the shadow map through; must be
+    /// rebuilt whenever `set_resolution` recreates the depth texture.
+    pub fn create_bind_group(
+        &self,
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Shadow Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&self.depth_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        })
+    }
+
+    /// Recreate the depth target at a new resolution (e.g. after the user tweaks
+    /// `shadow_map_resolution` in the lighting panel). Returns whether it actually changed, so
+    /// callers know whether the group-2 bind group (which points at the old texture) needs
+    /// rebuilding; a no-op otherwise.
+    pub fn set_resolution(&mut self, device: &wgpu::Device, resolution: u32) -> bool {
+        if resolution == self.resolution {
+            return false;
+        }
+        let (depth_texture, depth_view) = Self::create_depth_target(device, resolution);
+        self.depth_texture = depth_texture;
+        self.depth_view = depth_view;
+        self.resolution = resolution;
+        true
+    }
+
+    /// Render scene depth from the light's point of view. Call `light_buffer.update` with the
+    /// light's view-projection before this, same as the main pass's `camera_buffer`.
+    ///
+    /// Deliberately does not apply the main camera's frustum culling (see `State::render`): a
+    /// node just outside the camera's view can still cast a shadow the camera sees, so every
+    /// node is drawn here against the full, uncompacted `instance_buffer`.
+    pub fn render(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        mesh_buffers: &[MeshBuffers],
+        instance_buffer: &InstanceBuffer,
+        scene: &Scene,
+    ) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Shadow Map Pass"),
+            color_attachments: &[],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &self.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.light_buffer.bind_group, &[]);
+        render_pass.set_vertex_buffer(1, instance_buffer.buffer.slice(..));
+
+        let all_nodes: Vec<u32> = (0..scene.nodes.len() as u32).collect();
+        super::renderer::draw_batched_instances(&mut render_pass, mesh_buffers, scene, &all_nodes);
+    }
+}
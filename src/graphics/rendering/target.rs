@@ -0,0 +1,127 @@
+use super::context::HDR_COLOR_FORMAT;
+use super::context::GpuContext;
+
+/// Pixel rect `render_scene` restricts its drawing to within a `RenderTarget`, via
+/// `wgpu::RenderPass::set_viewport` - lets several cameras share one color/depth attachment pair
+/// for split-screen without each needing its own textures.
+#[derive(Debug, Clone, Copy)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Viewport {
+    /// The entire `width`x`height` target - what a single-camera `render_scene` call wants.
+    pub fn full(width: u32, height: u32) -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: width as f32,
+            height: height as f32,
+        }
+    }
+}
+
+/// Where one `render_scene` call draws: a color/depth attachment pair plus the `Viewport` rect
+/// within them, decoupling the pass from any particular swapchain or window. A caller renders
+/// the same `Scene` from several cameras per frame by building several `RenderTarget`s - either
+/// distinct `Viewport`s into one shared attachment pair (split-screen) or entirely separate
+/// attachments, e.g. from an `OffscreenTarget` (render-to-texture, a mirror).
+pub struct RenderTarget<'a> {
+    /// Final (non-multisampled) color attachment - the swapchain view, `GpuContext::hdr_color_view`,
+    /// or an `OffscreenTarget::color_view`.
+    pub view: &'a wgpu::TextureView,
+    /// Multisampled intermediate to render into and resolve from when MSAA is enabled; `None`
+    /// renders directly into `view`. See `GpuContext::msaa_color_view`.
+    pub msaa_view: Option<&'a wgpu::TextureView>,
+    pub depth_view: &'a wgpu::TextureView,
+    pub viewport: Viewport,
+    /// Whether this call should clear `view`/`depth_view` before drawing, or load the existing
+    /// contents - the first camera drawn into a shared attachment pair this frame clears it, any
+    /// others sharing it (a second split-screen viewport) must load instead or they'd wipe out
+    /// what the first camera already drew.
+    pub clear: bool,
+}
+
+impl<'a> RenderTarget<'a> {
+    pub fn new(
+        view: &'a wgpu::TextureView,
+        msaa_view: Option<&'a wgpu::TextureView>,
+        depth_view: &'a wgpu::TextureView,
+        viewport: Viewport,
+        clear: bool,
+    ) -> Self {
+        Self {
+            view,
+            msaa_view,
+            depth_view,
+            viewport,
+            clear,
+        }
+    }
+}
+
+/// A standalone color + depth texture pair, sized independently of the window's swapchain, for
+/// rendering the scene from a second camera into a texture instead of the screen - a mirror, a
+/// portal, a UI thumbnail. `target()` builds the `RenderTarget` to pass to `render_scene`, and
+/// `color_view` is the texture to later sample back in (e.g. as a post-process input); feeding it
+/// into a `Material` as a sampled input is left to whenever this crate's flat-color-only
+/// `Material` grows texture support.
+pub struct OffscreenTarget {
+    pub color_texture: wgpu::Texture,
+    pub color_view: wgpu::TextureView,
+    pub depth_view: wgpu::TextureView,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl OffscreenTarget {
+    pub fn new(device: &wgpu::Device, width: u32, height: u32) -> Self {
+        let width = width.max(1);
+        let height = height.max(1);
+
+        let color_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen Render Target Color"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: HDR_COLOR_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let color_view = color_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let depth_view = GpuContext::create_depth_texture(device, width, height, 1);
+
+        Self {
+            color_texture,
+            color_view,
+            depth_view,
+            width,
+            height,
+        }
+    }
+
+    /// `RenderTarget` covering this texture's full extent, always clearing - an offscreen target
+    /// isn't shared with another camera's viewport the way the main window's might be.
+    pub fn target(&self) -> RenderTarget<'_> {
+        RenderTarget::new(
+            &self.color_view,
+            None,
+            &self.depth_view,
+            Viewport::full(self.width, self.height),
+            true,
+        )
+    }
+
+    /// Recreate both textures at a new size, e.g. to track a resized source window it mirrors.
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        *self = Self::new(device, width, height);
+    }
+}
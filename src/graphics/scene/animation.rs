@@ -0,0 +1,94 @@
+use super::Transform;
+use glam::{Quat, Vec3};
+
+/// One sampled pose along a joint's track - see `JointTrack`.
+#[derive(Debug, Clone, Copy)]
+pub struct Keyframe {
+    pub time: f32,
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+/// A joint's keyframed TRS track, sampled with linear interpolation (`slerp` for rotation).
+/// Keyframes are expected sorted by `time`, as produced by a glTF sampler's input/output pairs.
+#[derive(Debug, Clone, Default)]
+pub struct JointTrack {
+    pub keyframes: Vec<Keyframe>,
+}
+
+impl JointTrack {
+    /// Sample the track at `time`, clamping to the first/last keyframe outside its range.
+    /// Returns `fallback` for a track with no keyframes, so a partially-keyframed clip still
+    /// gives an unanimated joint a sane (rest) pose instead of snapping to the identity.
+    pub fn sample(&self, time: f32, fallback: Transform) -> Transform {
+        let keyframes = &self.keyframes;
+        if keyframes.is_empty() {
+            return fallback;
+        }
+        if time <= keyframes[0].time {
+            return to_transform(&keyframes[0]);
+        }
+        let last = keyframes.len() - 1;
+        if time >= keyframes[last].time {
+            return to_transform(&keyframes[last]);
+        }
+
+        let next = keyframes.iter().position(|k| k.time > time).unwrap_or(last);
+        let prev = next - 1;
+        let (a, b) = (&keyframes[prev], &keyframes[next]);
+        let t = (time - a.time) / (b.time - a.time).max(f32::EPSILON);
+
+        Transform {
+            position: a.translation.lerp(b.translation, t),
+            rotation: a.rotation.slerp(b.rotation, t),
+            scale: a.scale.lerp(b.scale, t),
+        }
+    }
+}
+
+fn to_transform(keyframe: &Keyframe) -> Transform {
+    Transform {
+        position: keyframe.translation,
+        rotation: keyframe.rotation,
+        scale: keyframe.scale,
+    }
+}
+
+/// A skeletal animation: one `JointTrack` per joint, indexed the same way as the `Skeleton` it
+/// drives (see `SceneNode::animation_id`). Looping by default - `sample` wraps `time` by
+/// `duration` rather than clamping, since the common case (an idle/walk cycle) is meant to repeat.
+#[derive(Debug, Clone)]
+pub struct AnimationClip {
+    pub tracks: Vec<JointTrack>,
+    pub duration: f32,
+}
+
+impl AnimationClip {
+    pub fn new(tracks: Vec<JointTrack>, duration: f32) -> Self {
+        Self { tracks, duration }
+    }
+
+    /// Sample every joint's local transform at `time`, wrapped to `[0, duration)`. `rest_locals`
+    /// supplies the per-joint fallback (see `JointTrack::sample`) for any joint this clip doesn't
+    /// have a track for, e.g. a clip that only keyframes a subset of the skeleton.
+    pub fn sample(&self, time: f32, rest_locals: &[[[f32; 4]; 4]]) -> Vec<Transform> {
+        let time = if self.duration > 0.0 {
+            time.rem_euclid(self.duration)
+        } else {
+            0.0
+        };
+
+        rest_locals
+            .iter()
+            .enumerate()
+            .map(|(idx, rest_local)| {
+                let fallback = Transform::from_matrix(rest_local);
+                match self.tracks.get(idx) {
+                    Some(track) => track.sample(time, fallback),
+                    None => fallback,
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,278 @@
+use super::cpu_picking::{Ray, compute_node_aabb, ray_mesh_intersect, ray_sphere_intersect};
+use super::{AABB, Collider, Scene};
+use glam::{Mat4, Vec3};
+
+/// Primitives per leaf before it's no longer worth splitting further.
+const LEAF_SIZE: usize = 4;
+/// SAH bucket count along the split axis, following the usual "a dozen or so buckets is enough"
+/// rule of thumb (e.g. PBRT) - more buckets sharpen the split estimate but cost more to evaluate.
+const BUCKET_COUNT: usize = 12;
+
+fn axis_component(v: Vec3, axis: usize) -> f32 {
+    match axis {
+        0 => v.x,
+        1 => v.y,
+        _ => v.z,
+    }
+}
+
+fn union_aabb(a: &AABB, b: &AABB) -> AABB {
+    AABB::new(a.min().min(b.min()), a.max().max(b.max()))
+}
+
+fn empty_aabb() -> AABB {
+    AABB::new(Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY))
+}
+
+fn surface_area(aabb: &AABB) -> f32 {
+    let extent = aabb.max() - aabb.min();
+    2.0 * (extent.x * extent.y + extent.y * extent.z + extent.z * extent.x)
+}
+
+/// A scene node's world-space bounds, indexed by `node_id`, reordered freely during the build.
+struct Primitive {
+    node_id: u32,
+    aabb: AABB,
+    centroid: Vec3,
+}
+
+/// One entry in the flat BVH. Leaves (`count > 0`) reference a contiguous range of
+/// `Bvh::leaf_nodes`; interior nodes (`count == 0`) always have their left child immediately
+/// following them in `Bvh::nodes`, with `right_child` giving the other child's index.
+struct BvhNode {
+    aabb: AABB,
+    start: u32,
+    count: u32,
+    right_child: u32,
+    /// Split axis for interior nodes, used to visit the nearer child first during traversal.
+    axis: u8,
+}
+
+/// Bounding-volume hierarchy over a scene's selectable nodes, built bottom-up from their
+/// world-space `AABB`s, that turns a pick into roughly O(log n) AABB tests plus a handful of
+/// triangle tests at the leaves instead of a brute-force O(nodes x triangles) scan.
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    /// `node_id` per leaf slot, reordered to match the partitioning done while building `nodes`.
+    leaf_nodes: Vec<u32>,
+}
+
+impl Bvh {
+    /// Build the hierarchy from every selectable node's current `world_transforms` entry.
+    /// Callers should make sure `scene.update_transforms()` has already run this frame.
+    pub fn build(scene: &Scene) -> Self {
+        let mut primitives: Vec<Primitive> = scene
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.selectable)
+            .map(|(idx, node)| {
+                let mesh = &scene.meshes[node.mesh_id];
+                let transform = Mat4::from_cols_array_2d(&scene.world_transforms[idx]);
+                let aabb = compute_node_aabb(&mesh.vertices, transform);
+                let centroid = (aabb.min() + aabb.max()) * 0.5;
+                Primitive {
+                    node_id: idx as u32,
+                    aabb,
+                    centroid,
+                }
+            })
+            .collect();
+
+        let mut nodes = Vec::new();
+        if !primitives.is_empty() {
+            build_recursive(&mut primitives, 0, &mut nodes);
+        }
+
+        let leaf_nodes = primitives.into_iter().map(|p| p.node_id).collect();
+
+        Self { nodes, leaf_nodes }
+    }
+
+    /// Closest node hit by the ray, with its hit distance, or `None` if nothing was hit.
+    /// Descends front-to-back and prunes any subtree whose near-plane distance already exceeds
+    /// the closest hit found so far.
+    pub fn intersect(&self, scene: &Scene, ray_origin: Vec3, ray_dir: Vec3) -> Option<(u32, f32)> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+
+        // Built once and reused for every node's AABB test below, rather than recomputing the
+        // reciprocal direction and slab signs per test - the whole point of caching a `Ray`.
+        let ray = Ray::new(ray_origin, ray_dir);
+
+        let mut closest: Option<(u32, f32)> = None;
+        let mut stack = vec![0u32];
+
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index as usize];
+
+            let Some(tmin) = ray.aabb_intersect(&node.aabb) else {
+                continue;
+            };
+            if let Some((_, closest_t)) = closest {
+                if tmin > closest_t {
+                    continue;
+                }
+            }
+
+            if node.count > 0 {
+                for &node_id in
+                    &self.leaf_nodes[node.start as usize..(node.start + node.count) as usize]
+                {
+                    let scene_node = &scene.nodes[node_id as usize];
+                    let mesh = &scene.meshes[scene_node.mesh_id];
+                    let transform = Mat4::from_cols_array_2d(&scene.world_transforms[node_id as usize]);
+
+                    let hit = match scene_node.collider {
+                        Collider::Sphere { radius } => {
+                            let center = transform.transform_point3(Vec3::ZERO);
+                            ray_sphere_intersect(ray_origin, ray_dir, center, radius)
+                        }
+                        Collider::Mesh => {
+                            ray_mesh_intersect(ray_origin, ray_dir, &mesh.vertices, &mesh.indices, transform)
+                        }
+                    };
+
+                    if let Some(t) = hit {
+                        if closest.map_or(true, |(_, best_t)| t < best_t) {
+                            closest = Some((node_id, t));
+                        }
+                    }
+                }
+            } else {
+                let near = node_index + 1;
+                let far = node.right_child;
+                // Push the far child first so the near one (the side the ray starts on, along
+                // the split axis) pops and is visited first - the earlier a hit is found, the
+                // more of the remaining tree the `tmin` prune above can skip.
+                if ray_dir_component(ray_dir, node.axis) >= 0.0 {
+                    stack.push(far);
+                    stack.push(near);
+                } else {
+                    stack.push(near);
+                    stack.push(far);
+                }
+            }
+        }
+
+        closest
+    }
+}
+
+fn ray_dir_component(ray_dir: Vec3, axis: u8) -> f32 {
+    axis_component(ray_dir, axis as usize)
+}
+
+/// Recursively partition `primitives` (a sub-slice starting at `base_offset` within the full
+/// leaf array) into a flat node, pushed into `nodes`. Returns the new node's index.
+fn build_recursive(primitives: &mut [Primitive], base_offset: u32, nodes: &mut Vec<BvhNode>) -> u32 {
+    let bounds = primitives
+        .iter()
+        .fold(empty_aabb(), |acc, p| union_aabb(&acc, &p.aabb));
+
+    let node_index = nodes.len() as u32;
+    nodes.push(BvhNode {
+        aabb: bounds,
+        start: base_offset,
+        count: primitives.len() as u32,
+        right_child: 0,
+        axis: 0,
+    });
+
+    if primitives.len() <= LEAF_SIZE {
+        return node_index;
+    }
+
+    // Split axis: the one with the largest extent of primitive centroids.
+    let (centroid_min, centroid_max) = primitives.iter().fold(
+        (Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY)),
+        |(min, max), p| (min.min(p.centroid), max.max(p.centroid)),
+    );
+    let extent = centroid_max - centroid_min;
+    let axis = if extent.x >= extent.y && extent.x >= extent.z {
+        0
+    } else if extent.y >= extent.z {
+        1
+    } else {
+        2
+    };
+    let axis_min = axis_component(centroid_min, axis);
+    let axis_extent = axis_component(extent, axis);
+
+    let mut split_count = if axis_extent <= f32::EPSILON {
+        0 // Degenerate centroid bounds - every primitive sits at the same point on this axis.
+    } else {
+        let bucket_of = |p: &Primitive| -> usize {
+            let t = (axis_component(p.centroid, axis) - axis_min) / axis_extent;
+            ((t * BUCKET_COUNT as f32) as usize).min(BUCKET_COUNT - 1)
+        };
+
+        let mut bucket_aabb = std::array::from_fn::<AABB, BUCKET_COUNT, _>(|_| empty_aabb());
+        let mut bucket_count = [0u32; BUCKET_COUNT];
+        for p in primitives.iter() {
+            let b = bucket_of(p);
+            bucket_count[b] += 1;
+            bucket_aabb[b] = union_aabb(&bucket_aabb[b], &p.aabb);
+        }
+
+        // Sweep the SAH cost (area(left) * count(left) + area(right) * count(right)) over each
+        // of the `BUCKET_COUNT - 1` candidate splits between buckets, keeping the cheapest.
+        let mut best_cost = f32::INFINITY;
+        let mut best_split = 0usize;
+        for split in 1..BUCKET_COUNT {
+            let left_count: u32 = bucket_count[..split].iter().sum();
+            let right_count: u32 = bucket_count[split..].iter().sum();
+            if left_count == 0 || right_count == 0 {
+                continue;
+            }
+            let left_aabb = bucket_aabb[..split]
+                .iter()
+                .fold(empty_aabb(), |acc, b| union_aabb(&acc, b));
+            let right_aabb = bucket_aabb[split..]
+                .iter()
+                .fold(empty_aabb(), |acc, b| union_aabb(&acc, b));
+            let cost =
+                surface_area(&left_aabb) * left_count as f32 + surface_area(&right_aabb) * right_count as f32;
+            if cost < best_cost {
+                best_cost = cost;
+                best_split = split;
+            }
+        }
+
+        if best_split == 0 {
+            0
+        } else {
+            // Partition in place: primitives whose bucket is below the chosen split move left.
+            let mut i = 0;
+            for j in 0..primitives.len() {
+                if bucket_of(&primitives[j]) < best_split {
+                    primitives.swap(i, j);
+                    i += 1;
+                }
+            }
+            i
+        }
+    };
+
+    if split_count == 0 || split_count == primitives.len() {
+        // Bucketing failed to separate anything (e.g. many coincident centroids) - fall back to
+        // a plain median split by sorting on the chosen axis.
+        primitives.sort_by(|a, b| {
+            axis_component(a.centroid, axis)
+                .partial_cmp(&axis_component(b.centroid, axis))
+                .unwrap()
+        });
+        split_count = primitives.len() / 2;
+    }
+
+    let (left, right) = primitives.split_at_mut(split_count);
+    build_recursive(left, base_offset, nodes);
+    let right_index = build_recursive(right, base_offset + split_count as u32, nodes);
+
+    nodes[node_index as usize].count = 0;
+    nodes[node_index as usize].right_child = right_index;
+    nodes[node_index as usize].axis = axis as u8;
+
+    node_index
+}
@@ -1,6 +1,21 @@
+use super::cpu_picking::Ray;
 use glam::{Mat4, Vec3, Vec4};
 use std::f32::consts::PI;
 
+/// A camera's view-projection matrix, abstracted over `Camera` (orbit) and `FlyCamera` so
+/// `State::render` can read it from whichever one is active without knowing which it is.
+pub trait ViewProjection {
+    fn view_projection_matrix(&self) -> [[f32; 4]; 4];
+}
+
+/// Which camera `State` is currently driving. Switching doesn't reset either camera's state, so
+/// toggling back and forth (e.g. via Tab) resumes each one right where it was left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraMode {
+    Orbit,
+    Fly,
+}
+
 #[derive(Debug, Clone)]
 pub struct Camera {
     pub distance: f32,
@@ -56,26 +71,24 @@ impl Camera {
         (proj * view).to_cols_array_2d()
     }
 
-    /// Convert screen coordinates to a world-space ray
-    /// Returns (ray_origin, ray_direction)
-    pub fn screen_to_world_ray(
-        &self,
+    /// Convert screen coordinates (pixels, Y-down) to NDC (-1 to 1, Y-up).
+    pub fn screen_to_ndc(
         screen_x: f32,
         screen_y: f32,
         screen_width: f32,
         screen_height: f32,
-    ) -> (Vec3, Vec3) {
-        // Convert screen coords to NDC (-1 to 1, Y flipped for screen space)
+    ) -> (f32, f32) {
         let ndc_x = (2.0 * screen_x / screen_width) - 1.0;
         let ndc_y = 1.0 - (2.0 * screen_y / screen_height);
+        (ndc_x, ndc_y)
+    }
 
-        // Get view-projection matrix and invert it
-        let view_proj = Mat4::from_cols_array_2d(&self.view_projection_matrix());
-        let inv_view_proj = view_proj.inverse();
-
-        // Unproject near and far points in NDC space to world space
-        let near_ndc = Vec4::new(ndc_x, ndc_y, 0.0, 1.0);
-        let far_ndc = Vec4::new(ndc_x, ndc_y, 1.0, 1.0);
+    /// Unproject an NDC point through an inverse view-projection matrix into a world-space ray,
+    /// by unprojecting the near (z=0) and far (z=1) points and taking the direction between
+    /// them. Shared by `screen_to_world_ray` and `PickingState::drag_to_world`.
+    pub fn ndc_to_world_ray(ndc: (f32, f32), inv_view_proj: Mat4) -> (Vec3, Vec3) {
+        let near_ndc = Vec4::new(ndc.0, ndc.1, 0.0, 1.0);
+        let far_ndc = Vec4::new(ndc.0, ndc.1, 1.0, 1.0);
 
         let near_world = inv_view_proj * near_ndc;
         let far_world = inv_view_proj * far_ndc;
@@ -84,12 +97,46 @@ impl Camera {
         let near_world = near_world.truncate() / near_world.w;
         let far_world = far_world.truncate() / far_world.w;
 
-        // Ray from near to far
         let direction = (far_world - near_world).normalize();
 
         (near_world, direction)
     }
 
+    /// Convert screen coordinates to a world-space ray
+    /// Returns (ray_origin, ray_direction)
+    pub fn screen_to_world_ray(
+        &self,
+        screen_x: f32,
+        screen_y: f32,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> (Vec3, Vec3) {
+        let ndc = Self::screen_to_ndc(screen_x, screen_y, screen_width, screen_height);
+        let view_proj = Mat4::from_cols_array_2d(&self.view_projection_matrix());
+        Self::ndc_to_world_ray(ndc, view_proj.inverse())
+    }
+
+    /// Extract the six clip-space planes (left/right/bottom/top/near/far) of this camera's
+    /// combined `proj * view` matrix, for a fast broad-phase visibility test - see
+    /// `AABB::in_frustum` - before submitting a node for rendering or descending a `Bvh`.
+    pub fn frustum_planes(&self) -> [Vec4; 6] {
+        extract_frustum_planes(Mat4::from_cols_array_2d(&self.view_projection_matrix()))
+    }
+
+    /// Same as `screen_to_world_ray`, but returns the precomputed `Ray` form (cached reciprocal
+    /// direction and slab signs) directly, for callers about to test it against many AABBs -
+    /// e.g. picking - instead of rebuilding that cache themselves.
+    pub fn screen_to_world_ray_cached(
+        &self,
+        screen_x: f32,
+        screen_y: f32,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> Ray {
+        let (origin, dir) = self.screen_to_world_ray(screen_x, screen_y, screen_width, screen_height);
+        Ray::new(origin, dir)
+    }
+
     /// Find intersection of a ray with a plane
     /// Returns None if ray is parallel to plane or intersection is behind ray origin
     pub fn ray_plane_intersection(
@@ -116,3 +163,40 @@ impl Camera {
         Some(ray_origin + ray_dir * t)
     }
 }
+
+impl ViewProjection for Camera {
+    fn view_projection_matrix(&self) -> [[f32; 4]; 4] {
+        Camera::view_projection_matrix(self)
+    }
+}
+
+/// Extract the six clip-space planes (left/right/bottom/top/near/far) from a `proj * view`
+/// matrix via the Gribb/Hartmann method: each plane is a row combination of the matrix,
+/// `view_proj.transpose()` turning glam's columns into the rows the method is usually described
+/// in terms of, normalized so `xyz` is a unit normal and `w` a true signed distance. Shared by
+/// `Camera::frustum_planes` and `rendering::culling::Frustum::from_view_proj`, which builds one
+/// from an arbitrary view-projection matrix rather than a `Camera` directly.
+pub(crate) fn extract_frustum_planes(view_proj: Mat4) -> [Vec4; 6] {
+    let m = view_proj.transpose();
+    let row0 = m.x_axis;
+    let row1 = m.y_axis;
+    let row2 = m.z_axis;
+    let row3 = m.w_axis;
+
+    let mut planes = [
+        row3 + row0, // left
+        row3 - row0, // right
+        row3 + row1, // bottom
+        row3 - row1, // top
+        row2,        // near - wgpu's clip space depth runs 0..1, so the near plane is z >= 0
+        row3 - row2, // far
+    ];
+    for plane in &mut planes {
+        let length = Vec3::new(plane.x, plane.y, plane.z).length();
+        if length > 0.0 {
+            *plane /= length;
+        }
+    }
+
+    planes
+}
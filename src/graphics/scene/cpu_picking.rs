@@ -1,18 +1,122 @@
 use crate::graphics::geometry::Vertex;
-use glam::{Mat4, Vec3};
+use glam::{Mat4, Vec3, Vec4};
 
+/// Axis-aligned bounding box, stored as `[min, max]` rather than separate fields so a ray's
+/// cached `Ray::sign` can index straight into it (`bounds[sign[axis]]`) instead of branching per
+/// axis - see `Ray::aabb_intersect`.
 pub struct AABB {
-    pub min: Vec3,
-    pub max: Vec3,
+    bounds: [Vec3; 2],
+}
+
+impl AABB {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { bounds: [min, max] }
+    }
+
+    pub fn min(&self) -> Vec3 {
+        self.bounds[0]
+    }
+
+    pub fn max(&self) -> Vec3 {
+        self.bounds[1]
+    }
+
+    /// "Positive vertex" frustum test (Gribb/Hartmann): for each plane - `(a, b, c, d)` with
+    /// `a*x + b*y + c*z + d >= 0` on the inside half-space - picks the box corner furthest along
+    /// the plane's normal and rejects the box only if even that corner is still behind the plane.
+    /// `planes` is typically `Camera::frustum_planes`.
+    pub fn in_frustum(&self, planes: &[Vec4; 6]) -> bool {
+        planes.iter().all(|plane| {
+            let normal = Vec3::new(plane.x, plane.y, plane.z);
+            let positive = Vec3::new(
+                if normal.x >= 0.0 { self.max().x } else { self.min().x },
+                if normal.y >= 0.0 { self.max().y } else { self.min().y },
+                if normal.z >= 0.0 { self.max().z } else { self.min().z },
+            );
+            normal.dot(positive) + plane.w >= 0.0
+        })
+    }
+}
+
+/// A ray with its reciprocal direction and per-axis slab signs precomputed, so testing it
+/// against many AABBs (as picking does, once per scene node) only pays for the divide and the
+/// sign check once instead of on every test.
+pub struct Ray {
+    pub origin: Vec3,
+    pub dir: Vec3,
+    inv_dir: Vec3,
+    /// `1` where `inv_dir`'s component is negative, `0` otherwise - indexes `AABB::bounds` so the
+    /// min/max side picked for each axis needs no branch.
+    sign: [usize; 3],
+}
+
+impl Ray {
+    pub fn new(origin: Vec3, dir: Vec3) -> Self {
+        let inv_dir = Vec3::new(
+            if dir.x.abs() < 1e-7 { 1e7 } else { 1.0 / dir.x },
+            if dir.y.abs() < 1e-7 { 1e7 } else { 1.0 / dir.y },
+            if dir.z.abs() < 1e-7 { 1e7 } else { 1.0 / dir.z },
+        );
+        let sign = [
+            (inv_dir.x < 0.0) as usize,
+            (inv_dir.y < 0.0) as usize,
+            (inv_dir.z < 0.0) as usize,
+        ];
+
+        Self {
+            origin,
+            dir,
+            inv_dir,
+            sign,
+        }
+    }
+
+    /// Slab-method ray-AABB test (Williams et al., "An Efficient and Robust Ray-Box Intersection
+    /// Algorithm"), branchless in the min/max selection thanks to the precomputed `sign`.
+    /// Returns the entry distance along the ray if it hits, `None` if it misses.
+    pub fn aabb_intersect(&self, aabb: &AABB) -> Option<f32> {
+        let bounds = &aabb.bounds;
+
+        let mut tmin = (bounds[self.sign[0]].x - self.origin.x) * self.inv_dir.x;
+        let mut tmax = (bounds[1 - self.sign[0]].x - self.origin.x) * self.inv_dir.x;
+        let tymin = (bounds[self.sign[1]].y - self.origin.y) * self.inv_dir.y;
+        let tymax = (bounds[1 - self.sign[1]].y - self.origin.y) * self.inv_dir.y;
+
+        if tmin > tymax || tymin > tmax {
+            return None;
+        }
+        if tymin > tmin {
+            tmin = tymin;
+        }
+        if tymax < tmax {
+            tmax = tymax;
+        }
+
+        let tzmin = (bounds[self.sign[2]].z - self.origin.z) * self.inv_dir.z;
+        let tzmax = (bounds[1 - self.sign[2]].z - self.origin.z) * self.inv_dir.z;
+
+        if tmin > tzmax || tzmin > tmax {
+            return None;
+        }
+        if tzmin > tmin {
+            tmin = tzmin;
+        }
+        if tzmax < tmax {
+            tmax = tzmax;
+        }
+
+        if tmax >= 0.0 {
+            Some(tmin.max(0.0))
+        } else {
+            None
+        }
+    }
 }
 
 /// Compute axis-aligned bounding box for a mesh in world space
 pub fn compute_node_aabb(vertices: &[Vertex], transform_matrix: Mat4) -> AABB {
     if vertices.is_empty() {
-        return AABB {
-            min: Vec3::ZERO,
-            max: Vec3::ZERO,
-        };
+        return AABB::new(Vec3::ZERO, Vec3::ZERO);
     }
 
     let mut min = Vec3::splat(f32::INFINITY);
@@ -25,42 +129,16 @@ pub fn compute_node_aabb(vertices: &[Vertex], transform_matrix: Mat4) -> AABB {
         max = max.max(world_pos);
     }
 
-    AABB { min, max }
+    AABB::new(min, max)
 }
 
-/// Fast ray-AABB intersection using slab method
+/// Fast ray-AABB intersection using the slab method. Thin wrapper over `Ray::aabb_intersect` for
+/// one-off tests; callers doing many AABB tests against the same ray (e.g. picking) should build
+/// a `Ray` once and call `aabb_intersect` directly instead of recomputing the reciprocal direction
+/// every time.
 /// Returns distance along ray if hit, None if miss
 pub fn ray_aabb_intersect(ray_origin: Vec3, ray_dir: Vec3, aabb: &AABB) -> Option<f32> {
-    let inv_dir = Vec3::new(
-        if ray_dir.x.abs() < 1e-7 {
-            1e7
-        } else {
-            1.0 / ray_dir.x
-        },
-        if ray_dir.y.abs() < 1e-7 {
-            1e7
-        } else {
-            1.0 / ray_dir.y
-        },
-        if ray_dir.z.abs() < 1e-7 {
-            1e7
-        } else {
-            1.0 / ray_dir.z
-        },
-    );
-
-    let t1 = (aabb.min - ray_origin) * inv_dir;
-    let t2 = (aabb.max - ray_origin) * inv_dir;
-
-    let tmin = t1.min(t2).max_element();
-    let tmax = t1.max(t2).min_element();
-
-    // Check if ray intersects and is in front of camera
-    if tmax >= tmin && tmax >= 0.0 {
-        Some(tmin.max(0.0))
-    } else {
-        None
-    }
+    Ray::new(ray_origin, ray_dir).aabb_intersect(aabb)
 }
 
 /// MÃ¶ller-Trumbore ray-triangle intersection algorithm with backface culling
@@ -118,6 +196,31 @@ pub fn ray_triangle_intersect(
     if t > EPSILON { Some(t) } else { None }
 }
 
+/// Analytic ray-sphere intersection: solves `|ray_origin + t*ray_dir - center|^2 = radius^2` for
+/// the smallest non-negative `t`. Assumes `ray_dir` is normalized. Far cheaper and more precise
+/// than `ray_mesh_intersect` for point-like nodes rendered as spheres (`Mesh::uv_sphere`) - see
+/// `SceneNode::collider`.
+pub fn ray_sphere_intersect(ray_origin: Vec3, ray_dir: Vec3, center: Vec3, radius: f32) -> Option<f32> {
+    let k = center - ray_origin;
+    let a = ray_dir.dot(k);
+    let d = a * a - (k.dot(k) - radius * radius);
+    if d < 0.0 {
+        return None;
+    }
+
+    let sq = d.sqrt();
+    let t1 = a - sq;
+    let t2 = a + sq;
+
+    if t1 >= 0.0 {
+        Some(t1)
+    } else if t2 >= 0.0 {
+        Some(t2)
+    } else {
+        None
+    }
+}
+
 /// Test ray against all triangles in a mesh
 /// Returns closest hit distance, or None if no hit
 pub fn ray_mesh_intersect(
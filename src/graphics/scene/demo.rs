@@ -52,78 +52,105 @@ pub fn create_demo_scene() -> Scene {
         true,
     ));
 
-    // Additional cubes showing instancing
+    // Orange marker cube for the grid root - a pass-through parent (identity transform) that the
+    // instancing cubes and grid lines below are attached to, so moving/rotating just this one
+    // node carries the whole sub-assembly with it (see `Scene::update_transforms`).
+    scene.materials.push(Material::from_rgb(1.0, 0.5, 0.0)); // Orange, material_id = 5
+    let grid_root_idx = scene.nodes.len() as u32;
     scene.nodes.push(SceneNode::new(
         0,
-        0,
-        Transform::new([-4.0, 0.3, -3.0], [0.0, 0.0, 0.0], [0.6, 0.6, 0.6]),
+        5,
+        Transform::new([0.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.2, 0.2, 0.2]),
         true,
     ));
 
-    scene.nodes.push(SceneNode::new(
-        0,
-        1,
-        Transform::new([4.0, 0.3, -3.0], [0.0, 0.0, 0.0], [0.6, 0.6, 0.6]),
-        true,
-    ));
+    // Additional cubes showing instancing
+    scene.nodes.push(
+        SceneNode::new(
+            0,
+            0,
+            Transform::new([-4.0, 0.3, -3.0], [0.0, 0.0, 0.0], [0.6, 0.6, 0.6]),
+            true,
+        )
+        .with_parent(grid_root_idx),
+    );
 
-    scene.nodes.push(SceneNode::new(
-        0,
-        2,
-        Transform::new([0.0, 0.3, -4.0], [0.0, 0.0, 0.0], [0.6, 0.6, 0.6]),
-        true,
-    ));
+    scene.nodes.push(
+        SceneNode::new(
+            0,
+            1,
+            Transform::new([4.0, 0.3, -3.0], [0.0, 0.0, 0.0], [0.6, 0.6, 0.6]),
+            true,
+        )
+        .with_parent(grid_root_idx),
+    );
+
+    scene.nodes.push(
+        SceneNode::new(
+            0,
+            2,
+            Transform::new([0.0, 0.3, -4.0], [0.0, 0.0, 0.0], [0.6, 0.6, 0.6]),
+            true,
+        )
+        .with_parent(grid_root_idx),
+    );
 
     // Line segments forming a simple grid on the ground
     // Lines along X axis
     for i in -2..=2 {
-        scene.nodes.push(SceneNode::new(
-            2,
-            4,
-            Transform::new(
-                [0.0, 0.01, i as f32 * 2.0],
-                [0.0, 0.0, 0.0],
-                [8.0, 1.0, 1.0],
-            ),
-            true,
-        ));
+        scene.nodes.push(
+            SceneNode::new(
+                2,
+                4,
+                Transform::new(
+                    [0.0, 0.01, i as f32 * 2.0],
+                    [0.0, 0.0, 0.0],
+                    [8.0, 1.0, 1.0],
+                ),
+                true,
+            )
+            .with_parent(grid_root_idx),
+        );
     }
 
     // Lines along Z axis
     for i in -2..=2 {
-        scene.nodes.push(SceneNode::new(
-            2,
-            4,
-            Transform::new(
-                [i as f32 * 2.0, 0.01, 0.0],
-                [0.0, std::f32::consts::FRAC_PI_2, 0.0],
-                [8.0, 1.0, 1.0],
-            ),
-            true,
-        ));
+        scene.nodes.push(
+            SceneNode::new(
+                2,
+                4,
+                Transform::new(
+                    [i as f32 * 2.0, 0.01, 0.0],
+                    [0.0, std::f32::consts::FRAC_PI_2, 0.0],
+                    [8.0, 1.0, 1.0],
+                ),
+                true,
+            )
+            .with_parent(grid_root_idx),
+        );
     }
 
     // Parent/child hierarchy demonstration
     // Create yellow material for parent
-    scene.materials.push(Material::from_rgb(1.0, 1.0, 0.0)); // Yellow, material_id = 5
+    scene.materials.push(Material::from_rgb(1.0, 1.0, 0.0)); // Yellow, material_id = 6
 
     // Parent cube (yellow)
     let parent_idx = scene.nodes.len() as u32;
     scene.nodes.push(SceneNode::new(
         0,
-        5,
+        6,
         Transform::new([0.0, 1.5, -2.0], [0.0, 0.0, 0.0], [1.0, 1.0, 1.0]),
         true,
     ));
 
     // Create cyan material for first child
-    scene.materials.push(Material::from_rgb(0.0, 1.0, 1.0)); // Cyan, material_id = 6
+    scene.materials.push(Material::from_rgb(0.0, 1.0, 1.0)); // Cyan, material_id = 7
 
     // Child cube 1 (cyan) - offset to the right of parent (local space)
     scene.nodes.push(
         SceneNode::new(
             0,
-            6,
+            7,
             Transform::new([1.5, 0.0, 0.0], [0.0, 0.0, 0.0], [0.5, 0.5, 0.5]),
             true,
         )
@@ -131,13 +158,13 @@ pub fn create_demo_scene() -> Scene {
     );
 
     // Create magenta material for second child
-    scene.materials.push(Material::from_rgb(1.0, 0.0, 1.0)); // Magenta, material_id = 7
+    scene.materials.push(Material::from_rgb(1.0, 0.0, 1.0)); // Magenta, material_id = 8
 
     // Child cube 2 (magenta) - offset to the left of parent (local space)
     scene.nodes.push(
         SceneNode::new(
             0,
-            7,
+            8,
             Transform::new([-1.5, 0.0, 0.0], [0.0, 0.0, 0.0], [0.5, 0.5, 0.5]),
             true,
         )
@@ -145,14 +172,14 @@ pub fn create_demo_scene() -> Scene {
     );
 
     // Create white material for grandchild
-    scene.materials.push(Material::from_rgb(1.0, 1.0, 1.0)); // White, material_id = 8
+    scene.materials.push(Material::from_rgb(1.0, 1.0, 1.0)); // White, material_id = 9
 
     // Grandchild cube (white) - child of cyan cube
     let cyan_idx = (parent_idx + 1) as u32;
     scene.nodes.push(
         SceneNode::new(
             0,
-            8,
+            9,
             Transform::new([0.0, 1.0, 0.0], [0.0, 0.0, 0.0], [0.3, 0.3, 0.3]),
             true,
         )
@@ -0,0 +1,132 @@
+use super::camera::ViewProjection;
+use glam::{Mat4, Vec3};
+
+/// Free-look WASD camera, toggled alongside the orbit `Camera` (see `CameraMode`). Holds its own
+/// input state - pressed-direction flags and accumulated look deltas - rather than going through
+/// `CameraController`, since its movement model (position + pan/tilt, advanced every frame by
+/// `tick`) doesn't fit that struct's drag/scroll gesture tracking.
+#[derive(Debug, Clone)]
+pub struct FlyCamera {
+    pub position: Vec3,
+    /// Yaw, radians - rotation around world up.
+    pub pan: f32,
+    /// Pitch, radians - clamped to ±89° so the camera can never flip over looking straight up or
+    /// down.
+    pub tilt: f32,
+    aspect_ratio: f32,
+    forward_pressed: bool,
+    back_pressed: bool,
+    left_pressed: bool,
+    right_pressed: bool,
+    up_pressed: bool,
+    down_pressed: bool,
+    /// World units per second along the active movement directions.
+    speed: f32,
+}
+
+impl FlyCamera {
+    pub fn new(aspect_ratio: f32) -> Self {
+        Self {
+            position: Vec3::new(0.0, 2.0, 8.0),
+            pan: 0.0,
+            tilt: 0.0,
+            aspect_ratio,
+            forward_pressed: false,
+            back_pressed: false,
+            left_pressed: false,
+            right_pressed: false,
+            up_pressed: false,
+            down_pressed: false,
+            speed: 5.0,
+        }
+    }
+
+    pub fn update_aspect_ratio(&mut self, aspect_ratio: f32) {
+        self.aspect_ratio = aspect_ratio;
+    }
+
+    /// Set a direction's pressed flag from a WASD/space/shift key event. Returns whether `key`
+    /// was one this camera cares about, so callers can tell if the event was consumed.
+    pub fn set_direction_pressed(&mut self, key: FlyDirection, pressed: bool) {
+        match key {
+            FlyDirection::Forward => self.forward_pressed = pressed,
+            FlyDirection::Back => self.back_pressed = pressed,
+            FlyDirection::Left => self.left_pressed = pressed,
+            FlyDirection::Right => self.right_pressed = pressed,
+            FlyDirection::Up => self.up_pressed = pressed,
+            FlyDirection::Down => self.down_pressed = pressed,
+        }
+    }
+
+    /// Feed an already-sensitivity-scaled mouse-drag delta (see the `ORBIT` action in
+    /// `crate::input`, shared with the orbit `Camera`) into look rotation, clamping tilt to
+    /// straight up/down.
+    pub fn handle_mouse_drag(&mut self, delta_x: f32, delta_y: f32) {
+        self.pan += delta_x;
+
+        let max_tilt = 89.0_f32.to_radians();
+        self.tilt = (self.tilt - delta_y).clamp(-max_tilt, max_tilt);
+    }
+
+    fn forward(&self) -> Vec3 {
+        let (sin_pan, cos_pan) = self.pan.sin_cos();
+        let (sin_tilt, cos_tilt) = self.tilt.sin_cos();
+        Vec3::new(cos_tilt * sin_pan, sin_tilt, cos_tilt * cos_pan)
+    }
+
+    /// Advance `position` by one frame's worth of movement along whichever directions are
+    /// currently held, at `speed` world units per second.
+    pub fn tick(&mut self, delta: f32) {
+        let forward = self.forward();
+        let right = forward.cross(Vec3::Y).normalize();
+
+        let mut movement = Vec3::ZERO;
+        if self.forward_pressed {
+            movement += forward;
+        }
+        if self.back_pressed {
+            movement -= forward;
+        }
+        if self.right_pressed {
+            movement += right;
+        }
+        if self.left_pressed {
+            movement -= right;
+        }
+        if self.up_pressed {
+            movement += Vec3::Y;
+        }
+        if self.down_pressed {
+            movement -= Vec3::Y;
+        }
+
+        if movement.length_squared() > 0.0 {
+            self.position += movement.normalize() * self.speed * delta;
+        }
+    }
+
+    pub fn view_projection_matrix(&self) -> [[f32; 4]; 4] {
+        let view = Mat4::look_to_lh(self.position, self.forward(), Vec3::Y);
+        let proj = Mat4::perspective_lh(45.0_f32.to_radians(), self.aspect_ratio, 0.1, 100.0);
+        (proj * view).to_cols_array_2d()
+    }
+}
+
+impl ViewProjection for FlyCamera {
+    fn view_projection_matrix(&self) -> [[f32; 4]; 4] {
+        FlyCamera::view_projection_matrix(self)
+    }
+}
+
+/// One of the six movement directions `FlyCamera` tracks a pressed flag for, decoupled from any
+/// particular keyboard layout - callers (e.g. `State::handle_event`) map WASD/space/shift to
+/// these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlyDirection {
+    Forward,
+    Back,
+    Left,
+    Right,
+    Up,
+    Down,
+}
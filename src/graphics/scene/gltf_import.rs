@@ -0,0 +1,402 @@
+use super::{AnimationClip, Joint, JointTrack, Keyframe, Material, Scene, SceneNode, Skeleton, Transform};
+use crate::graphics::geometry::{Mesh, Vertex, VertexSkin};
+use glam::{Mat4, Quat, Vec3};
+
+/// Load a glTF/GLB file into a `Scene`: each primitive of each mesh becomes a `Mesh`, each
+/// material's base-color factor becomes a `Material`, each glTF node becomes a `SceneNode`
+/// with its local TRS copied into `Transform` and `parent_id` wired from the node hierarchy so
+/// `compute_world_transform` walks it the same way it already walks a procedurally-built scene,
+/// and each `skin` becomes a `Skeleton` (see `import_skeletons`) driving whichever primitive
+/// nodes reference it, animated by the first `document` animation that targets one of its joints
+/// (see `import_animations`) if any does.
+///
+/// Panics on a missing/unreadable/malformed file, matching `main.rs`'s `parse_network` - this is
+/// a command-line asset load, not a recoverable runtime path.
+pub fn load_gltf_scene(path: &str) -> Scene {
+    let (document, buffers, _images) =
+        gltf::import(path).unwrap_or_else(|e| panic!("Failed to load glTF file {}: {}", path, e));
+
+    let mut scene = Scene::new();
+
+    // A degenerate placeholder mesh for transform-only nodes (cameras, lights, pure group
+    // nodes) that still need a SceneNode to parent their children off of.
+    scene.meshes.push(Mesh {
+        vertices: Vec::new(),
+        indices: Vec::new(),
+        skin: None,
+    });
+
+    import_materials(&mut scene, &document);
+
+    let skin_joint_nodes: Vec<Vec<usize>> = document
+        .skins()
+        .map(|skin| skin.joints().map(|joint| joint.index()).collect())
+        .collect();
+    import_skeletons(&mut scene, &document, &buffers, &skin_joint_nodes);
+    let skin_animation_ids = import_animations(&mut scene, &document, &buffers, &skin_joint_nodes);
+
+    for gltf_scene in document.scenes().take(1) {
+        for node in gltf_scene.nodes() {
+            import_node(&mut scene, &node, &buffers, None, &skin_animation_ids);
+        }
+    }
+
+    scene
+}
+
+/// One primitive imported by `import_meshes_into_scene`, as a ready-to-use `SceneNode` reference:
+/// the ids of the `Mesh`/`Material` it was appended as, already adjusted for whatever `scene`
+/// already held before the import.
+pub struct ImportedMesh {
+    pub mesh_id: usize,
+    pub material_id: usize,
+}
+
+/// Import the meshes and materials of a glTF/GLB file into an already-existing `Scene`, without
+/// adding any nodes or reading the file's node hierarchy - for attaching real 3D models to nodes
+/// a caller builds itself (e.g. `network_to_scene` using an imported mesh instead of a cube for a
+/// particular transport mode) rather than replacing the whole scene the way `load_gltf_scene`
+/// does. Returns one `ImportedMesh` per primitive, in the same order `document.meshes()` yields
+/// them, so a caller can pick whichever it wants by index.
+///
+/// Doesn't import skins/animations - a caller wiring up its own nodes has no glTF node hierarchy
+/// to match joints against; use `load_gltf_scene` for a skinned asset.
+///
+/// Panics on a missing/unreadable/malformed file, matching `load_gltf_scene`.
+pub fn import_meshes_into_scene(scene: &mut Scene, path: &str) -> Vec<ImportedMesh> {
+    let (document, buffers, _images) =
+        gltf::import(path).unwrap_or_else(|e| panic!("Failed to load glTF file {}: {}", path, e));
+
+    let material_offset = scene.materials.len();
+    import_materials(scene, &document);
+
+    document
+        .meshes()
+        .flat_map(|mesh| mesh.primitives())
+        .map(|primitive| {
+            let mesh_id = import_primitive(scene, &primitive, &buffers);
+            let material_id = primitive
+                .material()
+                .index()
+                .map(|index| material_offset + index)
+                .unwrap_or(material_offset);
+            ImportedMesh { mesh_id, material_id }
+        })
+        .collect()
+}
+
+/// Append each of `document`'s materials as a `Material`, mapping the base-color factor into this
+/// crate's flat-color model (the only one `Material`/the shading pipeline currently support). If
+/// the file defines none, fall back to a single white material so `unwrap_or` callers always have
+/// at least one valid id to point at.
+fn import_materials(scene: &mut Scene, document: &gltf::Document) {
+    let before = scene.materials.len();
+    scene.materials.extend(document.materials().map(|material| {
+        let [r, g, b, _a] = material.pbr_metallic_roughness().base_color_factor();
+        Material::from_rgb(r, g, b)
+    }));
+    if scene.materials.len() == before {
+        scene.materials.push(Material::from_rgb(1.0, 1.0, 1.0));
+    }
+}
+
+/// Append one `Skeleton` per `document.skins()` entry to `scene.skeletons`, in skin order (so a
+/// skin's index doubles as its `Skeleton`'s id - see `SceneNode::with_skeleton`). Each joint's
+/// `rest_local` comes from its own glTF node's local TRS, and `parent` from whichever other joint
+/// in the same skin lists that node as a child - relying on exporters listing joints top-down,
+/// the same invariant `Skeleton::joint_matrices` checks with `debug_assert!`.
+fn import_skeletons(
+    scene: &mut Scene,
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    skin_joint_nodes: &[Vec<usize>],
+) {
+    for skin in document.skins() {
+        let reader = skin.reader(|buffer| Some(&buffers[buffer.index()]));
+        let inverse_bind_matrices: Vec<[[f32; 4]; 4]> = reader
+            .read_inverse_bind_matrices()
+            .map(|iter| iter.collect())
+            .unwrap_or_else(|| vec![Mat4::IDENTITY.to_cols_array_2d(); skin.joints().count()]);
+
+        let joint_nodes = &skin_joint_nodes[skin.index()];
+        let joints = skin
+            .joints()
+            .enumerate()
+            .map(|(i, joint_node)| {
+                let (translation, rotation, scale) = joint_node.transform().decomposed();
+                let rest_local = Transform {
+                    position: Vec3::from_array(translation),
+                    rotation: Quat::from_array(rotation),
+                    scale: Vec3::from_array(scale),
+                }
+                .to_matrix();
+
+                let parent = joint_nodes.iter().position(|&candidate| {
+                    document.nodes().nth(candidate).is_some_and(|candidate_node| {
+                        candidate_node.children().any(|child| child.index() == joint_node.index())
+                    })
+                });
+
+                Joint {
+                    parent: parent.map(|p| p as u32),
+                    inverse_bind_matrix: inverse_bind_matrices[i],
+                    rest_local,
+                }
+            })
+            .collect();
+
+        scene.skeletons.push(Skeleton::new(joints));
+    }
+}
+
+/// Build one `AnimationClip` per skin from the first `document.animations()` entry that targets
+/// any of that skin's joints, appending it to `scene.animations` and returning the resulting
+/// animation id indexed by skin index (`None` for a skin no animation drives - it just renders
+/// its bind pose, see `Scene::skinned_joint_matrices`).
+///
+/// glTF doesn't tie an animation to a particular skin, and a file could have several animations
+/// per skin (e.g. "walk", "idle"); this picks the first match rather than importing every clip,
+/// since there's nowhere yet to choose between them at runtime (see `SceneNode::animation_id`).
+fn import_animations(
+    scene: &mut Scene,
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    skin_joint_nodes: &[Vec<usize>],
+) -> Vec<Option<usize>> {
+    skin_joint_nodes
+        .iter()
+        .map(|joint_nodes| {
+            document
+                .animations()
+                .find_map(|animation| build_animation_clip(&animation, buffers, joint_nodes))
+                .map(|clip| {
+                    scene.animations.push(clip);
+                    scene.animations.len() - 1
+                })
+        })
+        .collect()
+}
+
+/// Raw per-component keyframes for one joint's track, read straight off its glTF channels -
+/// glTF stores translation/rotation/scale on independent timelines, so these aren't merged into
+/// a single `Keyframe` list until `merge_raw_track`.
+#[derive(Default)]
+struct RawJointTrack {
+    translations: Vec<(f32, Vec3)>,
+    rotations: Vec<(f32, Quat)>,
+    scales: Vec<(f32, Vec3)>,
+}
+
+/// Collect `animation`'s channels that target one of `joint_nodes`, keyed by joint index, and
+/// merge each into a `JointTrack`. Returns `None` if `animation` doesn't target any of them.
+fn build_animation_clip(
+    animation: &gltf::Animation,
+    buffers: &[gltf::buffer::Data],
+    joint_nodes: &[usize],
+) -> Option<AnimationClip> {
+    let mut raw_tracks: Vec<RawJointTrack> = (0..joint_nodes.len()).map(|_| RawJointTrack::default()).collect();
+    let mut matched = false;
+    let mut duration = 0.0_f32;
+
+    for channel in animation.channels() {
+        let target_node = channel.target().node().index();
+        let Some(joint_idx) = joint_nodes.iter().position(|&idx| idx == target_node) else {
+            continue;
+        };
+        matched = true;
+
+        let reader = channel.reader(|buffer| Some(&buffers[buffer.index()]));
+        let inputs: Vec<f32> = reader.read_inputs().map(|iter| iter.collect()).unwrap_or_default();
+        duration = duration.max(inputs.last().copied().unwrap_or(0.0));
+
+        match reader.read_outputs() {
+            Some(gltf::animation::util::ReadOutputs::Translations(values)) => {
+                raw_tracks[joint_idx].translations =
+                    inputs.iter().copied().zip(values.map(Vec3::from_array)).collect();
+            }
+            Some(gltf::animation::util::ReadOutputs::Rotations(values)) => {
+                raw_tracks[joint_idx].rotations = inputs
+                    .iter()
+                    .copied()
+                    .zip(values.into_f32().map(Quat::from_array))
+                    .collect();
+            }
+            Some(gltf::animation::util::ReadOutputs::Scales(values)) => {
+                raw_tracks[joint_idx].scales =
+                    inputs.iter().copied().zip(values.map(Vec3::from_array)).collect();
+            }
+            _ => {}
+        }
+    }
+
+    if !matched {
+        return None;
+    }
+
+    let tracks = raw_tracks.iter().map(merge_raw_track).collect();
+    Some(AnimationClip::new(tracks, duration))
+}
+
+/// Union a joint's (possibly independently-timed) T/R/S channels into one `JointTrack`,
+/// resampling whichever components don't share a timestamp with the others.
+fn merge_raw_track(raw: &RawJointTrack) -> JointTrack {
+    let mut times: Vec<f32> = raw
+        .translations
+        .iter()
+        .chain(raw.scales.iter())
+        .map(|(time, _)| *time)
+        .chain(raw.rotations.iter().map(|(time, _)| *time))
+        .collect();
+    times.sort_by(f32::total_cmp);
+    times.dedup_by(|a, b| (*a - *b).abs() < f32::EPSILON);
+
+    let keyframes = times
+        .into_iter()
+        .map(|time| Keyframe {
+            time,
+            translation: sample_vec3_channel(&raw.translations, time, Vec3::ZERO),
+            rotation: sample_quat_channel(&raw.rotations, time, Quat::IDENTITY),
+            scale: sample_vec3_channel(&raw.scales, time, Vec3::ONE),
+        })
+        .collect();
+
+    JointTrack { keyframes }
+}
+
+fn sample_vec3_channel(channel: &[(f32, Vec3)], time: f32, fallback: Vec3) -> Vec3 {
+    let Some(last) = channel.len().checked_sub(1) else {
+        return fallback;
+    };
+    if time <= channel[0].0 {
+        return channel[0].1;
+    }
+    if time >= channel[last].0 {
+        return channel[last].1;
+    }
+    let next = channel.iter().position(|(t, _)| *t > time).unwrap_or(last);
+    let (t0, v0) = channel[next - 1];
+    let (t1, v1) = channel[next];
+    v0.lerp(v1, (time - t0) / (t1 - t0).max(f32::EPSILON))
+}
+
+fn sample_quat_channel(channel: &[(f32, Quat)], time: f32, fallback: Quat) -> Quat {
+    let Some(last) = channel.len().checked_sub(1) else {
+        return fallback;
+    };
+    if time <= channel[0].0 {
+        return channel[0].1;
+    }
+    if time >= channel[last].0 {
+        return channel[last].1;
+    }
+    let next = channel.iter().position(|(t, _)| *t > time).unwrap_or(last);
+    let (t0, v0) = channel[next - 1];
+    let (t1, v1) = channel[next];
+    v0.slerp(v1, (time - t0) / (t1 - t0).max(f32::EPSILON))
+}
+
+/// Push a `SceneNode` for `node` (anchoring its transform so its mesh primitives and children
+/// can parent off it), then recurse into its children. A primitive node under a skinned mesh is
+/// wired to its skin's `Skeleton` (and that skeleton's animation, if one was imported for it).
+fn import_node(
+    scene: &mut Scene,
+    node: &gltf::Node,
+    buffers: &[gltf::buffer::Data],
+    parent_id: Option<u32>,
+    skin_animation_ids: &[Option<usize>],
+) {
+    let (translation, rotation, scale) = node.transform().decomposed();
+    let transform = Transform {
+        position: Vec3::from_array(translation),
+        rotation: Quat::from_array(rotation),
+        scale: Vec3::from_array(scale),
+    };
+
+    let mut anchor = SceneNode::new(0, 0, transform, false);
+    if let Some(parent_id) = parent_id {
+        anchor = anchor.with_parent(parent_id);
+    }
+    scene.nodes.push(anchor);
+    scene.dirty.push(true);
+    let anchor_id = (scene.nodes.len() - 1) as u32;
+
+    if let Some(mesh) = node.mesh() {
+        for primitive in mesh.primitives() {
+            let mesh_id = import_primitive(scene, &primitive, buffers);
+            let material_id = primitive
+                .material()
+                .index()
+                .map(|index| index.min(scene.materials.len() - 1))
+                .unwrap_or(0);
+
+            let mut primitive_node = SceneNode::new(mesh_id, material_id, Transform::identity(), true)
+                .with_parent(anchor_id);
+            if let Some(skin) = node.skin() {
+                primitive_node = primitive_node.with_skeleton(skin.index());
+                if let Some(animation_id) = skin_animation_ids[skin.index()] {
+                    primitive_node = primitive_node.with_animation(animation_id);
+                }
+            }
+
+            scene.nodes.push(primitive_node);
+            scene.dirty.push(true);
+        }
+    }
+
+    for child in node.children() {
+        import_node(scene, &child, buffers, Some(anchor_id), skin_animation_ids);
+    }
+}
+
+/// Read one primitive's positions/normals/indices (and, if present, `JOINTS_0`/`WEIGHTS_0`
+/// skinning attributes) into a new `Mesh`, returning its index.
+fn import_primitive(scene: &mut Scene, primitive: &gltf::Primitive, buffers: &[gltf::buffer::Data]) -> usize {
+    let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+
+    let positions: Vec<[f32; 3]> = reader
+        .read_positions()
+        .map(|iter| iter.collect())
+        .unwrap_or_default();
+    let normals: Vec<[f32; 3]> = reader
+        .read_normals()
+        .map(|iter| iter.collect())
+        .unwrap_or_else(|| vec![[0.0, 1.0, 0.0]; positions.len()]);
+    let indices: Vec<u32> = reader
+        .read_indices()
+        .map(|indices| indices.into_u32().collect())
+        .unwrap_or_else(|| (0..positions.len() as u32).collect());
+
+    let joint_indices: Option<Vec<[u32; 4]>> = reader
+        .read_joints(0)
+        .map(|iter| iter.into_u16().map(|joints| joints.map(u32::from)).collect());
+    let joint_weights: Option<Vec<[f32; 4]>> =
+        reader.read_weights(0).map(|iter| iter.into_f32().collect());
+
+    let vertices = positions
+        .into_iter()
+        .zip(normals)
+        .map(|(position, normal)| Vertex { position, normal })
+        .collect();
+
+    let mesh = Mesh {
+        vertices,
+        indices,
+        skin: None,
+    };
+    let mesh = match (joint_indices, joint_weights) {
+        (Some(joint_indices), Some(joint_weights)) => mesh.with_skin(
+            joint_indices
+                .into_iter()
+                .zip(joint_weights)
+                .map(|(joint_indices, joint_weights)| VertexSkin {
+                    joint_indices,
+                    joint_weights,
+                })
+                .collect(),
+        ),
+        _ => mesh,
+    };
+
+    scene.meshes.push(mesh);
+    scene.meshes.len() - 1
+}
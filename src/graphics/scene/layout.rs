@@ -0,0 +1,183 @@
+use super::{PickableKind, Scene};
+use glam::Vec3;
+use std::collections::HashMap;
+
+/// Reference area baked into the ideal-edge-length formula (`k = c * sqrt(AREA / pillar_count)`)
+/// so the only layout knob exposed to the user is `c` itself (see the egui panel).
+const AREA: f32 = 100.0;
+
+/// Fruchterman-Reingold force-directed layout that relaxes a network's pillars into a readable
+/// spread, stepped incrementally from `State::update`. Built from a `Scene`'s pillar/edge
+/// structure (see `rebuild`) rather than `model::Network` directly - everything the layout needs
+/// (which nodes are pillars, which pillars are connected) is already encoded in the `Scene` that
+/// `network::network_to_scene` built, so it stays usable even once the original `Network` is gone.
+pub struct ForceLayout {
+    pub running: bool,
+    /// Scales the ideal edge length `k = c * sqrt(AREA / pillar_count)` - higher spreads pillars
+    /// further apart.
+    pub c: f32,
+    /// Per-second multiplier applied to `temperature` so displacement settles down over time
+    /// instead of oscillating forever.
+    pub cooling_rate: f32,
+    /// Current per-second displacement cap; reset to `initial_temperature` by `start`/`rebuild`
+    /// and cooled by `cooling_rate` every `step`.
+    temperature: f32,
+    initial_temperature: f32,
+    pillars: Vec<u32>,
+    /// Pillar-pair edges, as indices into `pillars`.
+    edges: Vec<(usize, usize)>,
+}
+
+impl ForceLayout {
+    pub fn new() -> Self {
+        Self {
+            running: false,
+            c: 1.0,
+            cooling_rate: 0.5,
+            temperature: 0.0,
+            initial_temperature: 2.0,
+            pillars: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// Re-derive the pillar/edge structure this layout relaxes from `scene`: every
+    /// `PickableKind::Pillar` node becomes a movable point, and each `Scene::edge_node_refs`
+    /// entry whose two mode-layer endpoints belong to different pillars becomes one attractive
+    /// edge between those pillars. Call whenever the scene's network changes (currently just
+    /// once, in `State::new`).
+    pub fn rebuild(&mut self, scene: &Scene) {
+        self.pillars.clear();
+        self.edges.clear();
+
+        let mut pillar_index = HashMap::new();
+        for (idx, node) in scene.nodes.iter().enumerate() {
+            if node.kind == Some(PickableKind::Pillar) {
+                pillar_index.insert(idx as u32, self.pillars.len());
+                self.pillars.push(idx as u32);
+            }
+        }
+
+        for edge_ref in &scene.edge_node_refs {
+            let (from, to) = match edge_ref {
+                Some(pair) => *pair,
+                None => continue,
+            };
+            let from_pillar = scene.nodes[from as usize].parent_id;
+            let to_pillar = scene.nodes[to as usize].parent_id;
+            let (Some(from_pillar), Some(to_pillar)) = (from_pillar, to_pillar) else {
+                continue;
+            };
+            if from_pillar == to_pillar {
+                continue;
+            }
+            if let (Some(&a), Some(&b)) = (pillar_index.get(&from_pillar), pillar_index.get(&to_pillar)) {
+                self.edges.push((a, b));
+            }
+        }
+
+        self.temperature = self.initial_temperature;
+    }
+
+    /// Start (or restart, resetting the cooling schedule) the relaxation.
+    pub fn start(&mut self) {
+        self.temperature = self.initial_temperature;
+        self.running = true;
+    }
+
+    pub fn stop(&mut self) {
+        self.running = false;
+    }
+
+    /// Run one relaxation step: sum, per pillar, a repulsive force from every other pillar
+    /// (proportional to `k^2 / distance`) and an attractive force along every incident edge
+    /// (proportional to `distance^2 / k`), then move each non-pinned pillar by its resulting
+    /// displacement, capped to `temperature * delta` world units. Motion is constrained to the
+    /// X/Z plane - `Transform::position.y` is left untouched to match the existing drag behavior.
+    /// Returns the scene node ids that moved, so the caller can refresh their edges via
+    /// `network::update_network_edges`.
+    pub fn step(&mut self, scene: &mut Scene, pinned: Option<u32>, delta: f32) -> Vec<u32> {
+        if !self.running || self.pillars.len() < 2 {
+            return Vec::new();
+        }
+
+        let n = self.pillars.len();
+        let k = self.c * (AREA / n as f32).sqrt();
+
+        let positions: Vec<Vec3> = self
+            .pillars
+            .iter()
+            .map(|&id| scene.nodes[id as usize].transform.position)
+            .collect();
+
+        let mut displacement = vec![Vec3::ZERO; n];
+
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let separation = positions[i] - positions[j];
+                let dist = separation.length().max(0.01);
+                let force = (k * k) / dist;
+                let dir = separation / dist;
+                displacement[i] += dir * force;
+                displacement[j] -= dir * force;
+            }
+        }
+
+        for &(a, b) in &self.edges {
+            let separation = positions[a] - positions[b];
+            let dist = separation.length().max(0.01);
+            let force = (dist * dist) / k;
+            let dir = separation / dist;
+            displacement[a] -= dir * force;
+            displacement[b] += dir * force;
+        }
+
+        let cap = self.temperature * delta;
+        let mut moved = Vec::new();
+        for i in 0..n {
+            let pillar_id = self.pillars[i];
+            if Some(pillar_id) == pinned {
+                continue;
+            }
+
+            let disp = displacement[i];
+            let disp_len = disp.length();
+            if disp_len < 1e-5 {
+                continue;
+            }
+
+            let mut new_position = positions[i] + disp * (cap.min(disp_len) / disp_len);
+            new_position.y = 0.0;
+
+            scene.update_node_position(pillar_id, new_position);
+            moved.push(pillar_id);
+        }
+
+        self.temperature *= self.cooling_rate.powf(delta);
+
+        moved
+    }
+}
+
+impl Default for ForceLayout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Resolve the scene node id that should be pinned in place this frame because the user is
+/// currently dragging it - the dragged node itself if it's a pillar, or its parent pillar if it's
+/// one of the pillar's mode layers.
+pub fn pinned_pillar(scene: &Scene) -> Option<u32> {
+    if !scene.picking.is_node_locked() {
+        return None;
+    }
+
+    let picked = scene.picking.picked_node?;
+    let node = scene.nodes.get(picked as usize)?;
+    match node.kind {
+        Some(PickableKind::Pillar) => Some(picked),
+        Some(PickableKind::ModeLayer(_)) => node.parent_id,
+        _ => None,
+    }
+}
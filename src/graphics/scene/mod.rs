@@ -1,28 +1,69 @@
+mod animation;
+mod bvh;
 mod camera;
 mod cpu_picking;
 pub mod demo;
+mod flycam;
+pub mod gltf_import;
+mod layout;
 mod material;
 pub mod network;
 mod node;
 mod picking;
+pub mod script;
+mod skeleton;
 mod transform;
 
-pub use camera::Camera;
+pub use animation::{AnimationClip, JointTrack, Keyframe};
+pub use bvh::Bvh;
+pub use camera::{Camera, CameraMode, ViewProjection};
+pub use cpu_picking::{AABB, Ray};
+pub use flycam::{FlyCamera, FlyDirection};
+pub use layout::{ForceLayout, pinned_pillar};
 pub use material::Material;
-pub use node::SceneNode;
+pub use node::{Collider, PickableKind, SceneNode};
 pub use picking::PickingState;
+pub use script::ScriptedScene;
+pub use skeleton::{Joint, Skeleton};
 pub use transform::Transform;
 
 use crate::graphics::geometry::Mesh;
-use cpu_picking::{compute_node_aabb, ray_aabb_intersect, ray_mesh_intersect};
+use cpu_picking::{ray_mesh_intersect, ray_sphere_intersect};
 use glam::{Mat4, Vec3};
 
+/// Re-exported for frustum culling (`rendering::culling`), which tests the same per-node
+/// world-space AABB that picking already computes.
+pub(crate) use cpu_picking::compute_node_aabb;
+/// Re-exported so `rendering::culling::Frustum` can build its planes from an arbitrary
+/// view-projection matrix with the same math as `Camera::frustum_planes`.
+pub(crate) use camera::extract_frustum_planes;
+
 pub struct Scene {
     pub meshes: Vec<Mesh>,
     pub materials: Vec<Material>,
     pub nodes: Vec<SceneNode>,
     pub picking: PickingState,
     pub edge_node_refs: Vec<Option<(u32, u32)>>,
+    /// Joint hierarchies referenced by `SceneNode::skeleton_id`, parallel in spirit to `meshes`/
+    /// `materials` - populated by `gltf_import::load_gltf_scene` for a skinned glTF file.
+    pub skeletons: Vec<Skeleton>,
+    /// Skeletal animations referenced by `SceneNode::animation_id`, each indexed the same way as
+    /// whichever `Skeleton` it drives.
+    pub animations: Vec<AnimationClip>,
+    /// Per-node dirty flags: set whenever a node's world transform changes, so `State`'s
+    /// instanced-render cache and `world_transforms` below only touch the nodes that actually
+    /// moved instead of the whole scene every frame. Resized to match `nodes` by whoever owns
+    /// the scene (see `State::new`); cleared by `State` once it's consumed them for the frame.
+    pub dirty: Vec<bool>,
+    /// Cached world matrix per node, parallel to `nodes`. Stale until `update_transforms` has
+    /// run at least once after the last resize; populated top-down so every entry is valid by
+    /// the time its children read it.
+    pub world_transforms: Vec<[[f32; 4]; 4]>,
+    /// Cached acceleration structure for `pick_ray_bvh`, rebuilt lazily the next time it's
+    /// needed. `mark_dirty` invalidates it wholesale rather than refitting incrementally -
+    /// simple and still far cheaper than the brute-force scan it replaces for scenes with many
+    /// nodes, at the cost of a full rebuild on the first pick after any node moves.
+    bvh: Option<Bvh>,
 }
 
 impl Scene {
@@ -33,18 +74,63 @@ impl Scene {
             nodes: Vec::new(),
             picking: PickingState::new(),
             edge_node_refs: Vec::new(),
+            skeletons: Vec::new(),
+            animations: Vec::new(),
+            dirty: Vec::new(),
+            world_transforms: Vec::new(),
+            bvh: None,
         }
     }
 
+    /// Load a glTF/GLB file as a brand-new `Scene` - the `Scene`-side entry point for
+    /// `gltf_import::load_gltf_scene`, which does the actual primitive/material/node-tree
+    /// mapping and already reuses `meshes`/`materials`' positional-id dedup and preserves the
+    /// node hierarchy via `parent_id`.
+    pub fn from_gltf(path: &str) -> Scene {
+        gltf_import::load_gltf_scene(path)
+    }
+
     pub fn update_node_position(&mut self, node_id: u32, new_position: Vec3) {
         if let Some(node) = self.nodes.get_mut(node_id as usize) {
             node.transform.position = new_position;
         }
+        self.mark_dirty(node_id);
     }
 
-    /// Compute the world transform for a node by traversing its parent chain
-    /// Returns the combined world matrix
+    /// Mark a node, and every descendant whose world transform is derived from it, dirty for
+    /// the instanced-render cache.
+    pub fn mark_dirty(&mut self, node_id: u32) {
+        if let Some(flag) = self.dirty.get_mut(node_id as usize) {
+            *flag = true;
+        }
+        for descendant in self.get_descendants(node_id) {
+            if let Some(flag) = self.dirty.get_mut(descendant as usize) {
+                *flag = true;
+            }
+        }
+        self.bvh = None;
+    }
+
+    /// Compute the world transform for a node by traversing its parent chain. Recomputes the
+    /// full chain on every call - used while building a scene (`network_to_scene`,
+    /// `load_gltf_scene`), before `dirty`/`world_transforms` are sized to match `nodes`. Once a
+    /// scene is live, prefer `update_transforms` + reading `world_transforms` directly, which
+    /// does one linear pass over only the nodes that actually changed instead of a recursive
+    /// walk per query.
     pub fn compute_world_transform(&self, node_id: u32) -> [[f32; 4]; 4] {
+        self.compute_world_transform_bounded(node_id, 0)
+    }
+
+    /// Recursive parent-chain walk backing `compute_world_transform`, bounding depth so a
+    /// malformed `parent_id` cycle panics instead of recursing (and overflowing the stack)
+    /// forever.
+    fn compute_world_transform_bounded(&self, node_id: u32, depth: usize) -> [[f32; 4]; 4] {
+        const MAX_HIERARCHY_DEPTH: usize = 256;
+        assert!(
+            depth < MAX_HIERARCHY_DEPTH,
+            "scene hierarchy deeper than {MAX_HIERARCHY_DEPTH} levels at node {node_id} - check for a parent cycle"
+        );
+
         let node = &self.nodes[node_id as usize];
 
         match node.parent_id {
@@ -54,13 +140,69 @@ impl Scene {
             }
             Some(parent_id) => {
                 // Has parent - compute parent's world transform first (recursive)
-                let parent_world = self.compute_world_transform(parent_id);
+                let parent_world = self.compute_world_transform_bounded(parent_id, depth + 1);
                 let parent_matrix = Mat4::from_cols_array_2d(&parent_world);
                 node.transform.combine_with_parent(parent_matrix)
             }
         }
     }
 
+    /// Recompute `world_transforms` for every dirty node in one top-down pass instead of the
+    /// repeated recursive parent-chain walks `compute_world_transform` does per query. Relies on
+    /// every node appearing after its parent in `nodes` - true for every scene builder in this
+    /// crate (a node is always pushed right after the parent it was given) - so iterating in
+    /// index order guarantees a parent's cached matrix is already up to date before any child
+    /// reads it. Leaves `dirty` untouched; callers (`State::render`) clear it once they've also
+    /// consumed it for their own per-frame bookkeeping.
+    pub fn update_transforms(&mut self) {
+        if self.world_transforms.len() != self.nodes.len() {
+            self.world_transforms
+                .resize(self.nodes.len(), Mat4::IDENTITY.to_cols_array_2d());
+        }
+
+        for idx in 0..self.nodes.len() {
+            if !self.dirty.get(idx).copied().unwrap_or(true) {
+                continue;
+            }
+
+            let node = &self.nodes[idx];
+            self.world_transforms[idx] = match node.parent_id {
+                None => node.transform.to_matrix(),
+                Some(parent_id) => {
+                    // This single top-down pass only sees an up-to-date parent matrix if the
+                    // parent was already processed this loop - true as long as every node is
+                    // pushed after its parent (see the doc comment above). A parent index past
+                    // `idx` means that invariant (and likely a cycle) was violated.
+                    debug_assert!(
+                        (parent_id as usize) < idx,
+                        "node {idx} has parent {parent_id}, which isn't earlier in `nodes` - \
+                         check for a parent cycle or out-of-order insertion"
+                    );
+                    let parent_matrix = Mat4::from_cols_array_2d(&self.world_transforms[parent_id as usize]);
+                    node.transform.combine_with_parent(parent_matrix)
+                }
+            };
+        }
+    }
+
+    /// This frame's joint-palette matrices for a skinned node, or `None` if it isn't skinned
+    /// (`SceneNode::skeleton_id` is `None`). Samples `animation_id`'s clip at `time` if the node
+    /// has one, else every joint stays at its `Skeleton`'s rest (bind) pose - a skinned node with
+    /// no animation just renders rigidly in its authored pose.
+    pub fn skinned_joint_matrices(&self, node_id: u32, time: f32) -> Option<Vec<[[f32; 4]; 4]>> {
+        let node = self.nodes.get(node_id as usize)?;
+        let skeleton = self.skeletons.get(node.skeleton_id?)?;
+        let rest_locals: Vec<[[f32; 4]; 4]> = skeleton.joints.iter().map(|j| j.rest_local).collect();
+
+        let locals = match node.animation_id.and_then(|id| self.animations.get(id)) {
+            Some(clip) => clip.sample(time, &rest_locals),
+            None => return Some(skeleton.joint_matrices(&rest_locals)),
+        };
+        let locals: Vec<[[f32; 4]; 4]> = locals.iter().map(Transform::to_matrix).collect();
+
+        Some(skeleton.joint_matrices(&locals))
+    }
+
     /// Get all direct children of a node
     pub fn get_children(&self, parent_id: u32) -> Vec<u32> {
         self.nodes
@@ -96,7 +238,13 @@ impl Scene {
     /// Phase 2 (Narrow): Triangle-ray intersection with backface culling for precise results
     ///
     /// Call this during input processing for immediate, precise results
-    pub fn cpu_pick_ray(&self, ray_origin: Vec3, ray_dir: Vec3) -> Option<u32> {
+    pub fn cpu_pick_ray(&mut self, ray_origin: Vec3, ray_dir: Vec3) -> Option<u32> {
+        self.update_transforms();
+
+        // Built once and reused for every node's broad-phase AABB test below, instead of
+        // recomputing the reciprocal ray direction on each `ray_aabb_intersect` call.
+        let ray = Ray::new(ray_origin, ray_dir);
+
         let mut closest_t = f32::INFINITY;
         let mut closest_node = None;
 
@@ -106,24 +254,31 @@ impl Scene {
             }
 
             let mesh = &self.meshes[node.mesh_id];
-            let node_id = idx as u32;
-            let world_matrix = self.compute_world_transform(node_id);
-            let transform_matrix = Mat4::from_cols_array_2d(&world_matrix);
+            let transform_matrix = Mat4::from_cols_array_2d(&self.world_transforms[idx]);
 
             // Phase 1: Broad-phase AABB test (fast cull)
             let aabb = compute_node_aabb(&mesh.vertices, transform_matrix);
-            if ray_aabb_intersect(ray_origin, ray_dir, &aabb).is_none() {
+            if ray.aabb_intersect(&aabb).is_none() {
                 continue; // AABB miss - skip expensive triangle tests
             }
 
-            // Phase 2: Narrow-phase triangle intersection with backface culling (precise)
-            if let Some(t) = ray_mesh_intersect(
-                ray_origin,
-                ray_dir,
-                &mesh.vertices,
-                &mesh.indices,
-                transform_matrix,
-            ) {
+            // Phase 2: Narrow-phase intersection, dispatched on the node's collider - analytic
+            // and exact for a sphere, otherwise the general triangle-mesh test.
+            let hit = match node.collider {
+                Collider::Sphere { radius } => {
+                    let center = transform_matrix.transform_point3(Vec3::ZERO);
+                    ray_sphere_intersect(ray_origin, ray_dir, center, radius)
+                }
+                Collider::Mesh => ray_mesh_intersect(
+                    ray_origin,
+                    ray_dir,
+                    &mesh.vertices,
+                    &mesh.indices,
+                    transform_matrix,
+                ),
+            };
+
+            if let Some(t) = hit {
                 if t < closest_t {
                     closest_t = t;
                     closest_node = Some(idx as u32);
@@ -133,4 +288,42 @@ impl Scene {
 
         closest_node
     }
+
+    /// BVH-accelerated equivalent of `cpu_pick_ray` - builds (or reuses) a `Bvh` over the
+    /// scene's selectable nodes and descends it instead of scanning every node's AABB, which
+    /// pays off once the scene has enough nodes that the linear scan dominates. `cpu_pick_ray`
+    /// stays available as a simpler fallback.
+    pub fn pick_ray_bvh(&mut self, ray_origin: Vec3, ray_dir: Vec3) -> Option<u32> {
+        self.update_transforms();
+
+        if self.bvh.is_none() {
+            self.bvh = Some(Bvh::build(self));
+        }
+
+        let bvh = self.bvh.as_ref().unwrap();
+        bvh.intersect(self, ray_origin, ray_dir).map(|(node_id, _)| node_id)
+    }
+
+    /// World-space bounding box of every node, used to fit the shadow-map's orthographic
+    /// frustum tightly around the scene.
+    pub fn bounds(&mut self) -> AABB {
+        self.update_transforms();
+
+        let mut min = Vec3::splat(f32::INFINITY);
+        let mut max = Vec3::splat(f32::NEG_INFINITY);
+
+        for (idx, node) in self.nodes.iter().enumerate() {
+            let mesh = &self.meshes[node.mesh_id];
+            let transform_matrix = Mat4::from_cols_array_2d(&self.world_transforms[idx]);
+            let aabb = compute_node_aabb(&mesh.vertices, transform_matrix);
+            min = min.min(aabb.min());
+            max = max.max(aabb.max());
+        }
+
+        if self.nodes.is_empty() {
+            AABB::new(Vec3::ZERO, Vec3::ZERO)
+        } else {
+            AABB::new(min, max)
+        }
+    }
 }
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use glam::Vec3;
 
 use crate::graphics::geometry::Mesh;
-use crate::graphics::scene::{Material, Scene, SceneNode, Transform};
+use crate::graphics::scene::{Material, PickableKind, Scene, SceneNode, Transform};
 use crate::model::{Network, TransportMode};
 
 /// Convert a network into a 3D scene with hierarchical node pillars and dynamic edges
@@ -33,16 +33,19 @@ pub fn network_to_scene(network: &Network) -> Scene {
 
         // Create parent pillar at base height (Y=0)
         let pillar_id = scene.nodes.len() as u32;
-        scene.nodes.push(SceneNode::new(
-            0, // cube mesh
-            0, // pillar material (dark gray)
-            Transform::new(
-                [avg_pos[0], 0.0, avg_pos[1]], // Y=0 base
-                [0.0, 0.0, 0.0],
-                [0.5, 1.0, 0.5], // Tall thin box
-            ),
-            true, // selectable
-        ));
+        scene.nodes.push(
+            SceneNode::new(
+                0, // cube mesh
+                0, // pillar material (dark gray)
+                Transform::new(
+                    [avg_pos[0], 0.0, avg_pos[1]], // Y=0 base
+                    [0.0, 0.0, 0.0],
+                    [0.5, 1.0, 0.5], // Tall thin box
+                ),
+                true, // selectable
+            )
+            .with_kind(PickableKind::Pillar),
+        );
 
         // Sort mode_nodes for consistent layer ordering (Car -> Bike -> Walk -> Transit)
         let mut sorted_modes = mode_nodes.clone();
@@ -63,9 +66,10 @@ pub fn network_to_scene(network: &Network) -> Scene {
                         [0.0, 0.0, 0.0],
                         [0.15, 0.15, 0.15], // Keep existing size
                     ),
-                    false, // NOT selectable - only pillar can be picked
+                    true, // selectable - individual mode layers can be picked
                 )
-                .with_parent(pillar_id),
+                .with_parent(pillar_id)
+                .with_kind(PickableKind::ModeLayer(*mode)),
             );
 
             node_to_scene_id.insert((*mode, *node_idx), child_id);
@@ -96,12 +100,15 @@ pub fn network_to_scene(network: &Network) -> Scene {
                 let from_pos = extract_position_from_matrix(from_world);
                 let to_pos = extract_position_from_matrix(to_world);
 
-                scene.nodes.push(SceneNode::new(
-                    1, // line mesh
-                    mode_material_id(*mode),
-                    edge_transform_from_positions(from_pos, to_pos),
-                    false, // NOT selectable
-                ));
+                scene.nodes.push(
+                    SceneNode::new(
+                        1, // line mesh
+                        mode_material_id(*mode),
+                        edge_transform_from_positions(from_pos, to_pos),
+                        true, // selectable - edges report their transport mode when picked
+                    )
+                    .with_kind(PickableKind::Edge(*mode)),
+                );
 
                 // Track which nodes this edge connects (parallel to nodes array)
                 scene
@@ -127,18 +134,25 @@ pub fn update_network_edges(scene: &mut Scene, moved_node_id: u32) {
         vec![moved_node_id]
     };
 
+    // Refresh the moved subtree's cached world matrices before reading them below - cheap since
+    // only `moved_node_id` and its descendants are dirty at this point.
+    scene.update_transforms();
+
     // Update all edges connected to any affected node
     for (edge_idx, edge_ref) in scene.edge_node_refs.iter().enumerate() {
         if let Some((from_id, to_id)) = edge_ref {
             if affected_ids.contains(from_id) || affected_ids.contains(to_id) {
                 // Recalculate edge transform based on new endpoint positions
-                let from_world = scene.compute_world_transform(*from_id);
-                let to_world = scene.compute_world_transform(*to_id);
+                let from_world = scene.world_transforms[*from_id as usize];
+                let to_world = scene.world_transforms[*to_id as usize];
 
                 let from_pos = extract_position_from_matrix(from_world);
                 let to_pos = extract_position_from_matrix(to_world);
 
                 scene.nodes[edge_idx].transform = edge_transform_from_positions(from_pos, to_pos);
+                if let Some(flag) = scene.dirty.get_mut(edge_idx) {
+                    *flag = true;
+                }
             }
         }
     }
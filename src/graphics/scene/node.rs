@@ -1,4 +1,28 @@
 use super::Transform;
+use crate::model::TransportMode;
+
+/// What category of scene element a node represents, so the picking/hover UI can show
+/// human-readable context ("Edge (Transit)") instead of a raw node index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickableKind {
+    /// The dark-gray pillar grouping every mode layer at one location.
+    Pillar,
+    /// A single mode's layer stacked inside a pillar (e.g. the Walk layer).
+    ModeLayer(TransportMode),
+    /// A line segment connecting two mode-layer nodes.
+    Edge(TransportMode),
+}
+
+/// Shape a node picks against, independent of the mesh it renders with. Point-like nodes that
+/// render as a sphere (`Mesh::uv_sphere`) can pick against the cheap analytic
+/// `ray_sphere_intersect` instead of walking their mesh's triangles.
+#[derive(Debug, Clone, Copy)]
+pub enum Collider {
+    /// Pick against the node's actual render-mesh triangles - the general-purpose default.
+    Mesh,
+    /// Pick against a sphere of `radius` centered on the node's world position.
+    Sphere { radius: f32 },
+}
 
 #[derive(Debug, Clone)]
 pub struct SceneNode {
@@ -7,6 +31,14 @@ pub struct SceneNode {
     pub transform: Transform,
     pub selectable: bool,
     pub parent_id: Option<u32>,
+    pub kind: Option<PickableKind>,
+    pub collider: Collider,
+    /// Index into `Scene::skeletons`, for a joint-animated node - see
+    /// `Scene::skinned_joint_matrices`. `None` for a rigidly-transformed node, the common case.
+    pub skeleton_id: Option<usize>,
+    /// Index into `Scene::animations` currently driving `skeleton_id`. `None` plays the
+    /// skeleton's rest (bind) pose.
+    pub animation_id: Option<usize>,
 }
 
 impl SceneNode {
@@ -17,11 +49,39 @@ impl SceneNode {
             transform,
             selectable,
             parent_id: None,
+            kind: None,
+            collider: Collider::Mesh,
+            skeleton_id: None,
+            animation_id: None,
         }
     }
 
+    /// Drive this node's mesh with `skeleton_id`'s joint palette (see
+    /// `Scene::skinned_joint_matrices`) instead of rendering it rigidly.
+    pub fn with_skeleton(mut self, skeleton_id: usize) -> Self {
+        self.skeleton_id = Some(skeleton_id);
+        self
+    }
+
+    /// Sample `animation_id`'s clip to pose `skeleton_id` every frame, instead of holding its
+    /// rest pose. Meaningless without `with_skeleton`.
+    pub fn with_animation(mut self, animation_id: usize) -> Self {
+        self.animation_id = Some(animation_id);
+        self
+    }
+
     pub fn with_parent(mut self, parent_id: u32) -> Self {
         self.parent_id = Some(parent_id);
         self
     }
+
+    pub fn with_kind(mut self, kind: PickableKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    pub fn with_collider(mut self, collider: Collider) -> Self {
+        self.collider = collider;
+        self
+    }
 }
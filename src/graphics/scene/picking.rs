@@ -1,5 +1,7 @@
-use super::{Scene, SceneNode};
-use glam::Vec3;
+use super::{Camera, PickableKind, Scene, SceneNode};
+use crate::model::TransportMode;
+use glam::{Mat4, Vec3};
+use std::collections::HashSet;
 
 #[derive(Debug, Default)]
 pub struct PickingState {
@@ -7,6 +9,11 @@ pub struct PickingState {
     pub hovered_node: Option<u32>,
     /// Currently picked node ID (locked during drag)
     pub picked_node: Option<u32>,
+    /// Nodes covered by the last resolved marquee (box-select) pick - see
+    /// `PickingPass::request_pick_region`/`poll_result_region`. Replaced wholesale by each new
+    /// marquee, rather than accumulated, so dragging a fresh box always starts from an empty
+    /// selection.
+    pub selected_nodes: HashSet<u32>,
     drag: Option<DragState>,
 }
 
@@ -15,6 +22,8 @@ struct DragState {
     last_mouse_pos: (f32, f32),
     node_locked: bool,
     drag_offset: Option<Vec3>,
+    /// Height of the horizontal drag plane - the picked node's origin Y at lock time.
+    drag_plane_y: f32,
 }
 
 impl Default for DragState {
@@ -23,6 +32,7 @@ impl Default for DragState {
             last_mouse_pos: (0.0, 0.0),
             node_locked: false,
             drag_offset: None,
+            drag_plane_y: 0.0,
         }
     }
 }
@@ -56,6 +66,7 @@ impl PickingState {
             last_mouse_pos: mouse_pos,
             node_locked: false,
             drag_offset: None,
+            drag_plane_y: 0.0,
         });
     }
 
@@ -79,11 +90,14 @@ impl PickingState {
         self.drag.is_some()
     }
 
-    /// Lock the current drag to a specific node with an offset
-    pub fn lock_node_with_offset(&mut self, offset: Vec3) {
+    /// Lock the current drag to a specific node, storing the offset between the ray-plane hit
+    /// point that started the drag and the node's origin, and the height of the horizontal
+    /// plane (the node's own Y) that later hits are intersected against.
+    pub fn lock_node_with_offset(&mut self, offset: Vec3, drag_plane_y: f32) {
         if let Some(drag) = self.drag.as_mut() {
             drag.node_locked = true;
             drag.drag_offset = Some(offset);
+            drag.drag_plane_y = drag_plane_y;
         }
     }
 
@@ -97,8 +111,81 @@ impl PickingState {
         self.drag.as_ref().and_then(|d| d.drag_offset)
     }
 
+    /// Cast a world-space ray from `mouse_ndc` (see `Camera::screen_to_ndc`) through
+    /// `inv_view_proj` and intersect it with the locked drag's horizontal plane, returning the
+    /// hit point. Callers subtract `get_drag_offset()` from the result to get the node's new
+    /// position. Returns `None` if there's no locked drag or the ray is (near-)parallel to the
+    /// plane, e.g. looking straight along the horizon - in which case the node simply doesn't
+    /// move this frame.
+    pub fn drag_to_world(&self, mouse_ndc: (f32, f32), inv_view_proj: &[[f32; 4]; 4]) -> Option<Vec3> {
+        let drag = self.drag.as_ref()?;
+        let inv_view_proj = Mat4::from_cols_array_2d(inv_view_proj);
+        let (ray_origin, ray_dir) = Camera::ndc_to_world_ray(mouse_ndc, inv_view_proj);
+
+        Camera::ray_plane_intersection(
+            ray_origin,
+            ray_dir,
+            Vec3::new(0.0, drag.drag_plane_y, 0.0),
+            Vec3::Y,
+        )
+    }
+
     /// Update the hovered node (continuously updated, independent of drag)
     pub fn update_hovered_node(&mut self, node_id: Option<u32>) {
         self.hovered_node = node_id;
     }
+
+    /// Replace the marquee selection with the nodes a resolved region pick covered.
+    pub fn set_selection(&mut self, nodes: HashSet<u32>) {
+        self.selected_nodes = nodes;
+    }
+
+    /// Clear the marquee selection, e.g. when the user starts a fresh box-select.
+    pub fn clear_selection(&mut self) {
+        self.selected_nodes.clear();
+    }
+
+    pub fn is_selected(&self, node_id: u32) -> bool {
+        self.selected_nodes.contains(&node_id)
+    }
+
+    /// Human-readable description of the hovered node's category, e.g. "Edge (Transit)" or
+    /// "Walk layer of pillar 3", for the `hover_info` egui panel.
+    pub fn describe_hovered(&self, scene: &Scene) -> Option<String> {
+        self.hovered_node.and_then(|id| describe_node(scene, id))
+    }
+
+    /// Human-readable description of the picked node's category (see `describe_hovered`).
+    pub fn describe_picked(&self, scene: &Scene) -> Option<String> {
+        self.picked_node.and_then(|id| describe_node(scene, id))
+    }
+}
+
+/// Describe a node's `PickableKind`, resolving an edge's endpoints through
+/// `Scene::edge_node_refs` so a picked edge can report which two nodes it connects.
+fn describe_node(scene: &Scene, node_id: u32) -> Option<String> {
+    let node = scene.nodes.get(node_id as usize)?;
+    Some(match node.kind {
+        Some(PickableKind::Pillar) => format!("Pillar {}", node_id),
+        Some(PickableKind::ModeLayer(mode)) => match node.parent_id {
+            Some(parent_id) => format!("{} layer of pillar {}", mode_label(mode), parent_id),
+            None => format!("{} layer", mode_label(mode)),
+        },
+        Some(PickableKind::Edge(mode)) => {
+            match scene.edge_node_refs.get(node_id as usize).copied().flatten() {
+                Some((from, to)) => format!("Edge ({}) [{} -> {}]", mode_label(mode), from, to),
+                None => format!("Edge ({})", mode_label(mode)),
+            }
+        }
+        None => format!("Node {}", node_id),
+    })
+}
+
+fn mode_label(mode: TransportMode) -> &'static str {
+    match mode {
+        TransportMode::Car => "Car",
+        TransportMode::Bike => "Bike",
+        TransportMode::Walk => "Walk",
+        TransportMode::Transit => "Transit",
+    }
 }
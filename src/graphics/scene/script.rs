@@ -0,0 +1,194 @@
+use super::{Material, Scene, SceneNode, Transform};
+use crate::graphics::geometry::Mesh;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, FLOAT, INT, Scope, AST};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::SystemTime;
+
+/// The `Scene` handle exposed to scripts as the global `scene` variable. Registered as a `rhai`
+/// custom type so scripts call `scene.add_mesh("cube")`/`add_material(...)`/`add_node(...)` with
+/// method syntax, mirroring `Mesh::cube`/`Material::from_rgb`/`SceneNode::new` +
+/// `Transform::new` - rhai's `Dynamic` storage requires `Clone`, so this just wraps the real
+/// `Scene` in `Rc<RefCell<_>>` rather than trying to hand the engine the `Scene` itself.
+#[derive(Clone)]
+struct SceneHandle(Rc<RefCell<Scene>>);
+
+fn array_to_floats(array: Array) -> [f32; 3] {
+    let mut out = [0.0; 3];
+    for (slot, value) in out.iter_mut().zip(array) {
+        *slot = value.as_float().unwrap_or(0.0) as f32;
+    }
+    out
+}
+
+impl SceneHandle {
+    /// Push a mesh by name and return its `mesh_id`, matching the primitives `create_demo_scene`
+    /// builds from: `"cube"`, `"quad"`, or `"line"`. An unrecognized `kind` is a catchable script
+    /// error rather than a panic - this runs from user/hot-reloaded `.rhai` text, where a typo'd
+    /// mesh name is ordinary bad input, not a host bug.
+    fn add_mesh(&mut self, kind: &str) -> Result<INT, Box<EvalAltResult>> {
+        let mesh = match kind {
+            "cube" => Mesh::cube(),
+            "quad" => Mesh::quad(),
+            "line" => Mesh::line_segment(0.05),
+            other => {
+                return Err(
+                    format!("unknown mesh kind \"{other}\" (expected \"cube\", \"quad\", or \"line\")")
+                        .into(),
+                );
+            }
+        };
+        let mut scene = self.0.borrow_mut();
+        scene.meshes.push(mesh);
+        Ok((scene.meshes.len() - 1) as INT)
+    }
+
+    /// Push a flat-color material and return its `material_id` - mirrors `Material::from_rgb`.
+    fn add_material(&mut self, r: FLOAT, g: FLOAT, b: FLOAT) -> INT {
+        let mut scene = self.0.borrow_mut();
+        scene.materials.push(Material::from_rgb(r as f32, g as f32, b as f32));
+        (scene.materials.len() - 1) as INT
+    }
+
+    /// Push a node and return its id - mirrors `SceneNode::new(mesh_id, material_id,
+    /// Transform::new(translation, rotation, scale), true)`.
+    fn add_node(
+        &mut self,
+        mesh_id: INT,
+        material_id: INT,
+        translation: Array,
+        rotation: Array,
+        scale: Array,
+    ) -> INT {
+        let transform = Transform::new(
+            array_to_floats(translation),
+            array_to_floats(rotation),
+            array_to_floats(scale),
+        );
+        let mut scene = self.0.borrow_mut();
+        scene
+            .nodes
+            .push(SceneNode::new(mesh_id as usize, material_id as usize, transform, true));
+        (scene.nodes.len() - 1) as INT
+    }
+
+    /// Replace a node's transform wholesale - the binding `update(time)` hooks use to animate a
+    /// node, since `Scene::update_node_position` only ever touches `position`.
+    fn set_transform(&mut self, node_id: INT, translation: Array, rotation: Array, scale: Array) {
+        let transform = Transform::new(
+            array_to_floats(translation),
+            array_to_floats(rotation),
+            array_to_floats(scale),
+        );
+        let mut scene = self.0.borrow_mut();
+        if let Some(node) = scene.nodes.get_mut(node_id as usize) {
+            node.transform = transform;
+        }
+        scene.mark_dirty(node_id as u32);
+    }
+}
+
+fn build_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .register_type_with_name::<SceneHandle>("Scene")
+        .register_fn("add_mesh", SceneHandle::add_mesh)
+        .register_fn("add_material", SceneHandle::add_material)
+        .register_fn("add_node", SceneHandle::add_node)
+        .register_fn("set_transform", SceneHandle::set_transform);
+    engine
+}
+
+/// A scene defined by a `rhai` script instead of compiled-in Rust (the scripted counterpart to
+/// `demo::create_demo_scene`). The script runs once at `load`/`reload` to build the scene via
+/// `scene.add_mesh`/`add_material`/`add_node`, then its `update(time)` function (if any) is
+/// called every frame from `State::update` to mutate node transforms live.
+pub struct ScriptedScene {
+    engine: Engine,
+    ast: AST,
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl ScriptedScene {
+    /// Compile and run `path` into a brand-new `Scene`. Panics on a parse/eval error - there's no
+    /// sensible partial scene to fall back to if the script itself is broken.
+    pub fn load(path: impl AsRef<Path>) -> (Self, Scene) {
+        let path = path.as_ref().to_path_buf();
+        let engine = build_engine();
+        let ast = engine
+            .compile_file(path.clone())
+            .unwrap_or_else(|e| panic!("Failed to compile script {}: {}", path.display(), e));
+
+        let scene = Rc::new(RefCell::new(Scene::new()));
+        run_build(&engine, &ast, &path, scene.clone());
+        let scene = Rc::try_unwrap(scene)
+            .unwrap_or_else(|_| panic!("script {} kept a reference to the scene", path.display()))
+            .into_inner();
+
+        let modified = std::fs::metadata(&path).ok().and_then(|m| m.modified().ok());
+        (
+            Self {
+                engine,
+                ast,
+                path,
+                modified,
+            },
+            scene,
+        )
+    }
+
+    /// Re-compile and re-run the script into `scene` if its mtime has changed since the last
+    /// `load`/`reload_if_changed` - polled once a frame from `State::update`, which is cheap
+    /// enough that pulling in a file-watcher crate (there's no precedent for one in this repo)
+    /// isn't worth it. Logs and keeps the previous `ast` on a compile error, rather than leaving
+    /// the scene half-rebuilt.
+    pub fn reload_if_changed(&mut self, scene: &mut Scene) {
+        let modified = std::fs::metadata(&self.path).ok().and_then(|m| m.modified().ok());
+        if modified.is_none() || modified == self.modified {
+            return;
+        }
+
+        match self.engine.compile_file(self.path.clone()) {
+            Ok(ast) => {
+                self.ast = ast;
+                self.modified = modified;
+                let handle = Rc::new(RefCell::new(Scene::new()));
+                run_build(&self.engine, &self.ast, &self.path, handle.clone());
+                *scene = Rc::try_unwrap(handle)
+                    .unwrap_or_else(|_| panic!("script {} kept a reference to the scene", self.path.display()))
+                    .into_inner();
+            }
+            Err(e) => {
+                eprintln!("Failed to reload script {}: {e}", self.path.display());
+            }
+        }
+    }
+
+    /// Call the script's `update(time)` function, if it defines one, to mutate node transforms
+    /// this frame via `scene.set_transform`. A script with no `update` just defines a static
+    /// scene, which is fine - the call is silently skipped.
+    pub fn update(&self, scene: &mut Scene, time: f32) {
+        let handle = SceneHandle(Rc::new(RefCell::new(std::mem::replace(scene, Scene::new()))));
+        let mut scope = Scope::new();
+        scope.push("scene", handle.clone());
+        let _: Result<Dynamic, _> =
+            self.engine
+                .call_fn(&mut scope, &self.ast, "update", (time as FLOAT,));
+        // Drop the scope's own reference to `handle` before unwrapping it, or the Rc still has
+        // two owners and `try_unwrap` always fails.
+        drop(scope);
+        *scene = Rc::try_unwrap(handle.0)
+            .unwrap_or_else(|_| panic!("script {} kept a reference to the scene", self.path.display()))
+            .into_inner();
+    }
+}
+
+fn run_build(engine: &Engine, ast: &AST, path: &Path, scene: Rc<RefCell<Scene>>) {
+    let mut scope = Scope::new();
+    scope.push("scene", SceneHandle(scene));
+    engine
+        .eval_ast_with_scope::<Dynamic>(&mut scope, ast)
+        .unwrap_or_else(|e| panic!("Failed to run script {}: {}", path.display(), e));
+}
@@ -0,0 +1,62 @@
+use glam::Mat4;
+
+/// One joint in a `Skeleton`'s hierarchy.
+#[derive(Debug, Clone, Copy)]
+pub struct Joint {
+    /// Index into the owning `Skeleton::joints`, `None` for the root - always earlier than this
+    /// joint's own index, same invariant `Scene::update_transforms` relies on for `parent_id`.
+    pub parent: Option<u32>,
+    /// Inverse of this joint's bind-pose world transform, baked in at import time so
+    /// `Skeleton::joint_matrices` only has to combine it with the joint's *current* world
+    /// transform to get the final skin matrix.
+    pub inverse_bind_matrix: [[f32; 4]; 4],
+    /// This joint's local transform at rest (bind pose), used whenever `Scene::skinned_joint_matrices`
+    /// has no `AnimationClip` to sample instead - a skinned node with no animation still renders in
+    /// its authored pose rather than collapsing to the identity transform.
+    pub rest_local: [[f32; 4]; 4],
+}
+
+/// A skinning joint hierarchy referenced by `SceneNode::skeleton_id` - the standard glTF
+/// "SimpleSkin" model: each joint's skin matrix is `joint_world * inverse_bind_matrix`, recomputed
+/// every frame from whichever local transforms are currently driving it (see
+/// `Scene::skinned_joint_matrices`).
+#[derive(Debug, Clone)]
+pub struct Skeleton {
+    pub joints: Vec<Joint>,
+}
+
+impl Skeleton {
+    pub fn new(joints: Vec<Joint>) -> Self {
+        Self { joints }
+    }
+
+    /// Compose `locals` (one per joint, same order as `joints`) into skin matrices ready to
+    /// upload to the joint palette: each joint's world transform - built top-down, since every
+    /// joint's parent is guaranteed earlier in `joints` - times its inverse bind matrix.
+    pub fn joint_matrices(&self, locals: &[[[f32; 4]; 4]]) -> Vec<[[f32; 4]; 4]> {
+        let mut world = vec![Mat4::IDENTITY; self.joints.len()];
+
+        for (idx, joint) in self.joints.iter().enumerate() {
+            let local = Mat4::from_cols_array_2d(&locals[idx]);
+            world[idx] = match joint.parent {
+                Some(parent) => {
+                    debug_assert!(
+                        (parent as usize) < idx,
+                        "joint {idx} has parent {parent}, which isn't earlier in `joints` - \
+                         check for a parent cycle or out-of-order insertion"
+                    );
+                    world[parent as usize] * local
+                }
+                None => local,
+            };
+        }
+
+        world
+            .iter()
+            .zip(&self.joints)
+            .map(|(world, joint)| {
+                (*world * Mat4::from_cols_array_2d(&joint.inverse_bind_matrix)).to_cols_array_2d()
+            })
+            .collect()
+    }
+}
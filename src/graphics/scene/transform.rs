@@ -24,6 +24,18 @@ impl Transform {
         }
     }
 
+    /// Decompose a world/local matrix back into a `Transform` - used to recover a joint's rest
+    /// pose from its baked `[[f32; 4]; 4]` (see `AnimationClip::sample`'s `rest_locals` fallback).
+    pub fn from_matrix(matrix: &[[f32; 4]; 4]) -> Self {
+        let (scale, rotation, position) =
+            Mat4::from_cols_array_2d(matrix).to_scale_rotation_translation();
+        Self {
+            position,
+            rotation,
+            scale,
+        }
+    }
+
     pub fn to_matrix(&self) -> [[f32; 4]; 4] {
         let translation = Mat4::from_translation(self.position);
         let rotation = Mat4::from_quat(self.rotation);
@@ -31,4 +43,11 @@ impl Transform {
 
         (translation * rotation * scale).to_cols_array_2d()
     }
+
+    /// This transform's local matrix premultiplied by `parent`'s world matrix - the world matrix
+    /// for a node whose parent's world matrix is already known, without re-decomposing either
+    /// side back into a `Transform`.
+    pub fn combine_with_parent(&self, parent: Mat4) -> [[f32; 4]; 4] {
+        (parent * Mat4::from_cols_array_2d(&self.to_matrix())).to_cols_array_2d()
+    }
 }
@@ -5,3 +5,11 @@ pub fn cube_shader_source() -> String {
 pub fn picking_shader_source() -> &'static str {
     include_str!("picking.wgsl")
 }
+
+pub fn shadow_shader_source() -> &'static str {
+    include_str!("shadow.wgsl")
+}
+
+pub fn tonemap_shader_source() -> &'static str {
+    include_str!("tonemap.wgsl")
+}
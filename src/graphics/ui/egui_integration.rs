@@ -1,6 +1,18 @@
 use egui_wgpu::ScreenDescriptor;
 use winit::{event::WindowEvent, window::Window};
 
+/// Output of [`EguiIntegration::begin`], carrying whatever egui produced (shapes, platform
+/// output, texture deltas) from a frame's `ui_fn` through to [`EguiIntegration::paint`] - kept
+/// separate from the frame's render pass so the caller can run its own scene render graph in
+/// between without re-entering egui.
+pub struct PreparedUi {
+    shapes: Vec<egui::epaint::ClippedShape>,
+    textures_delta: egui::TexturesDelta,
+    pixels_per_point: f32,
+    config_width: u32,
+    config_height: u32,
+}
+
 pub struct EguiIntegration {
     renderer: egui_wgpu::Renderer,
     context: egui::Context,
@@ -14,7 +26,7 @@ impl EguiIntegration {
         window: &Window,
     ) -> Self {
         let context = egui::Context::default();
-        let renderer = egui_wgpu::Renderer::new(device, surface_format, Default::default());
+        let renderer = egui_wgpu::Renderer::new(device, surface_format, None, 1, false);
         let state = egui_winit::State::new(
             context.clone(),
             egui::ViewportId::ROOT,
@@ -35,17 +47,16 @@ impl EguiIntegration {
         self.state.on_window_event(window, event).consumed
     }
 
-    pub fn render(
+    /// Run `ui_fn` against a fresh egui frame and hand back what it produced for `paint` to
+    /// upload and draw later in the frame, once the caller's own render graph has had a chance
+    /// to run in between.
+    pub fn begin(
         &mut self,
-        device: &wgpu::Device,
-        queue: &wgpu::Queue,
-        encoder: &mut wgpu::CommandEncoder,
-        view: &wgpu::TextureView,
         window: &Window,
         config_width: u32,
         config_height: u32,
         ui_fn: impl FnOnce(&egui::Context),
-    ) {
+    ) -> PreparedUi {
         let raw_input = self.state.take_egui_input(window);
         self.context.begin_pass(raw_input);
 
@@ -54,21 +65,40 @@ impl EguiIntegration {
         let egui_output = self.context.end_pass();
         self.state
             .handle_platform_output(window, egui_output.platform_output);
+
+        PreparedUi {
+            shapes: egui_output.shapes,
+            textures_delta: egui_output.textures_delta,
+            pixels_per_point: self.context.pixels_per_point(),
+            config_width,
+            config_height,
+        }
+    }
+
+    /// Upload and draw whatever `begin` prepared, as a render pass loading (not clearing) `view`.
+    pub fn paint(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        prepared: PreparedUi,
+    ) {
         let paint_jobs = self
             .context
-            .tessellate(egui_output.shapes, self.context.pixels_per_point());
+            .tessellate(prepared.shapes, prepared.pixels_per_point);
 
         let screen_descriptor = ScreenDescriptor {
-            size_in_pixels: [config_width, config_height],
-            pixels_per_point: self.context.pixels_per_point(),
+            size_in_pixels: [prepared.config_width, prepared.config_height],
+            pixels_per_point: prepared.pixels_per_point,
         };
 
-        for (id, image_delta) in &egui_output.textures_delta.set {
+        for (id, image_delta) in &prepared.textures_delta.set {
             self.renderer
                 .update_texture(device, queue, *id, image_delta);
         }
 
-        for id in &egui_output.textures_delta.free {
+        for id in &prepared.textures_delta.free {
             self.renderer.free_texture(id);
         }
 
@@ -89,7 +119,6 @@ impl EguiIntegration {
                     load: wgpu::LoadOp::Load,
                     store: wgpu::StoreOp::Store,
                 },
-                depth_slice: None,
             })],
             depth_stencil_attachment: None,
             timestamp_writes: None,
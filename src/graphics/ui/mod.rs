@@ -0,0 +1,5 @@
+mod egui_integration;
+pub mod panels;
+
+pub use egui_integration::{EguiIntegration, PreparedUi};
+pub use panels::{CameraDebugInfo, RenderStats};
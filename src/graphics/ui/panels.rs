@@ -1,4 +1,6 @@
-use crate::graphics::rendering::LightingControls;
+use crate::graphics::rendering::{LightingControls, PointLight, ShadowFilterMode, ToneMappingOperator};
+use crate::graphics::scene::ForceLayout;
+use crate::input::{ALL_ACTIONS, ActionKind, ActionLayout, action_kind};
 use egui::Ui;
 
 #[derive(Clone, Copy)]
@@ -16,6 +18,10 @@ pub struct RenderStats {
     pub node_count: usize,
     pub vertex_count: usize,
     pub material_count: usize,
+    /// Nodes actually submitted for drawing this frame after frustum culling.
+    pub drawn_count: usize,
+    /// Nodes skipped this frame because they failed the frustum test.
+    pub culled_count: usize,
     pub current_fps: f32,
     pub avg_fps_1s: f32,
     pub avg_fps_5s: f32,
@@ -82,27 +88,200 @@ pub fn lighting(ui: &mut Ui, controls: &mut LightingControls) {
     }
 
     ui.add(egui::Slider::new(&mut controls.ambient_height, 0.1..=20.0).text("Ambient height"));
+
+    ui.separator();
+    ui.label("Shadows");
+
+    ui.add(egui::Slider::new(&mut controls.shadow_bias, 0.0001..=0.05).text("Shadow bias"));
+
+    let mut resolution_log2 = controls.shadow_map_resolution.trailing_zeros();
+    if ui
+        .add(egui::Slider::new(&mut resolution_log2, 9..=13).text("Shadow map resolution (log2)"))
+        .changed()
+    {
+        controls.shadow_map_resolution = 1 << resolution_log2;
+    }
+    ui.monospace(format!(
+        "{0}x{0}",
+        controls.shadow_map_resolution
+    ));
+
+    egui::ComboBox::from_label("Shadow filter")
+        .selected_text(match controls.filter_mode {
+            ShadowFilterMode::None => "None",
+            ShadowFilterMode::Hardware2x2 => "Hardware 2x2",
+            ShadowFilterMode::Pcf { .. } => "PCF",
+            ShadowFilterMode::Pcss { .. } => "PCSS",
+        })
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut controls.filter_mode, ShadowFilterMode::None, "None");
+            ui.selectable_value(
+                &mut controls.filter_mode,
+                ShadowFilterMode::Hardware2x2,
+                "Hardware 2x2",
+            );
+            ui.selectable_value(
+                &mut controls.filter_mode,
+                ShadowFilterMode::Pcf { radius: 2 },
+                "PCF",
+            );
+            ui.selectable_value(
+                &mut controls.filter_mode,
+                ShadowFilterMode::Pcss {
+                    blocker_search_radius: 4,
+                    light_size: 0.5,
+                },
+                "PCSS",
+            );
+        });
+
+    match &mut controls.filter_mode {
+        ShadowFilterMode::None => {}
+        ShadowFilterMode::Hardware2x2 => {}
+        ShadowFilterMode::Pcf { radius } => {
+            let mut value = *radius;
+            if ui
+                .add(egui::Slider::new(&mut value, 1..=8).text("PCF radius"))
+                .changed()
+            {
+                *radius = value;
+            }
+        }
+        ShadowFilterMode::Pcss {
+            blocker_search_radius,
+            light_size,
+        } => {
+            let mut radius = *blocker_search_radius;
+            if ui
+                .add(egui::Slider::new(&mut radius, 1..=8).text("Blocker search radius"))
+                .changed()
+            {
+                *blocker_search_radius = radius;
+            }
+            ui.add(egui::Slider::new(light_size, 0.05..=2.0).text("Light size"));
+        }
+    }
+
+    ui.separator();
+    ui.label("Point Lights");
+    if ui.button("Add point light").clicked() {
+        controls.add_point_light(PointLight {
+            position: glam::Vec3::new(0.0, 2.0, 0.0),
+            color: glam::Vec3::new(1.0, 0.9, 0.7),
+            intensity: 2.0,
+            radius: 8.0,
+        });
+    }
+    let mut to_remove = None;
+    for (i, light) in controls.point_lights.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.label(format!("#{i}"));
+            ui.add(egui::DragValue::new(&mut light.position.x).speed(0.1).prefix("x:"));
+            ui.add(egui::DragValue::new(&mut light.position.y).speed(0.1).prefix("y:"));
+            ui.add(egui::DragValue::new(&mut light.position.z).speed(0.1).prefix("z:"));
+            ui.add(egui::Slider::new(&mut light.intensity, 0.0..=10.0).text("intensity"));
+            ui.add(egui::Slider::new(&mut light.radius, 0.5..=30.0).text("radius"));
+            if ui.button("Remove").clicked() {
+                to_remove = Some(i);
+            }
+        });
+    }
+    if let Some(i) = to_remove {
+        controls.remove_point_light(i);
+    }
+
+    ui.separator();
+    ui.label("Tone Mapping");
+
+    ui.add(egui::Slider::new(&mut controls.exposure, 0.1..=8.0).text("Exposure"));
+
+    egui::ComboBox::from_label("Operator")
+        .selected_text(match controls.tone_mapping {
+            ToneMappingOperator::Reinhard => "Reinhard",
+            ToneMappingOperator::Aces => "ACES (filmic)",
+        })
+        .show_ui(ui, |ui| {
+            ui.selectable_value(
+                &mut controls.tone_mapping,
+                ToneMappingOperator::Reinhard,
+                "Reinhard",
+            );
+            ui.selectable_value(
+                &mut controls.tone_mapping,
+                ToneMappingOperator::Aces,
+                "ACES (filmic)",
+            );
+        });
+}
+
+/// Sensitivity sliders for every bound `Axis` action, letting the user retune mouse/scroll
+/// sensitivity without editing `ActionLayout::default_bindings`. `Button` actions aren't editable
+/// here yet - rebinding them to a different key/mouse button would need a "press a key" capture
+/// widget this panel doesn't have.
+pub fn input_bindings(ui: &mut Ui, layout: &mut ActionLayout) {
+    ui.label("Input Bindings");
+    for &action in ALL_ACTIONS {
+        if action_kind(action) != ActionKind::Axis {
+            continue;
+        }
+        match layout.axes.get_mut(action) {
+            Some(binding) => {
+                ui.add(egui::Slider::new(&mut binding.scale, -2.0..=2.0).text(action));
+            }
+            None => {
+                ui.monospace(format!("{action}: unbound"));
+            }
+        }
+    }
+}
+
+/// Start/stop toggle and `c`/cooling-rate sliders for the Fruchterman-Reingold layout relaxation
+/// (see `scene::ForceLayout`). Toggling "Running" on always restarts the cooling schedule, so
+/// re-enabling after it's settled gives the network another pass instead of doing nothing.
+pub fn force_layout(ui: &mut Ui, layout: &mut ForceLayout) {
+    ui.label("Force-Directed Layout");
+
+    let mut running = layout.running;
+    if ui.checkbox(&mut running, "Running").changed() {
+        if running {
+            layout.start();
+        } else {
+            layout.stop();
+        }
+    }
+
+    ui.add(egui::Slider::new(&mut layout.c, 0.1..=5.0).text("C (spread)"));
+    ui.add(egui::Slider::new(&mut layout.cooling_rate, 0.0..=1.0).text("Cooling rate"));
 }
 
-pub fn hover_info(ui: &mut Ui, hovered_node_id: Option<u32>) {
+pub fn hover_info(ui: &mut Ui, hovered: Option<String>, selected_count: usize) {
     ui.label("Hover");
-    match hovered_node_id {
-        Some(node_id) => {
-            ui.monospace(format!("Hovered Node ID: {}", node_id));
+    match hovered {
+        Some(description) => {
+            ui.monospace(description);
         }
         None => {
-            ui.monospace("Hovered Node ID: None");
+            ui.monospace("Hovered: None");
         }
     }
+
+    if selected_count > 0 {
+        ui.monospace(format!("Selected: {selected_count} (right-drag to box-select)"));
+    }
 }
 
-pub fn render_stats(ui: &mut Ui, stats: &RenderStats) {
+pub fn render_stats(ui: &mut Ui, stats: &RenderStats, cull_enabled: &mut bool) {
     ui.label("Rendering Statistics");
+    ui.checkbox(cull_enabled, "Frustum culling");
     ui.monospace(format!(
         "FPS: {:.1} (1s: {:.1}, 5s: {:.1})",
         stats.current_fps, stats.avg_fps_1s, stats.avg_fps_5s
     ));
     ui.monospace(format!("Nodes: {}", stats.node_count));
+    ui.monospace(format!(
+        "Drawn: {}  Culled: {}",
+        stats.drawn_count, stats.culled_count
+    ));
     ui.monospace(format!("Vertices: {}", stats.vertex_count));
     ui.monospace(format!("Materials: {}", stats.material_count));
 }
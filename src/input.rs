@@ -0,0 +1,352 @@
+//! Action-mapping layer that sits between raw winit events and the systems that respond to
+//! them (the orbit/fly cameras, node-drag picking). Input semantics (`ORBIT`, `ZOOM`, `PICK`, ...)
+//! are named and typed independently of whatever mouse button, key, or gesture currently drives
+//! them, so remapping controls or tuning sensitivity is a matter of editing an `ActionLayout`
+//! rather than the event-handling code itself.
+
+use std::collections::HashMap;
+use winit::event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::keyboard::{Key, NamedKey};
+
+/// Whether an action reports a continuous value (`Axis`, e.g. a mouse-drag delta) or a discrete
+/// press/release (`Button`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionKind {
+    Axis,
+    Button,
+}
+
+/// Identifies a named action independent of whatever raw input currently drives it. A plain
+/// `&'static str` rather than an enum so new actions (e.g. from a future input plugin) don't need
+/// to extend a central type.
+pub type ActionId = &'static str;
+
+pub const ORBIT: ActionId = "ORBIT";
+/// Registered per the action set this layer ships with, but unbound by default - the orbit
+/// camera's target is fixed at the origin, so there's no existing gesture to wire it to yet. A
+/// future rebind panel can still assign it a source.
+pub const PAN: ActionId = "PAN";
+pub const ZOOM: ActionId = "ZOOM";
+pub const PICK: ActionId = "PICK";
+/// Held-and-dragged to box-select - resolved via `PickingPass::request_pick_region` rather than
+/// the single-pixel path `PICK` drives. Bound to the right mouse button so it doesn't fight
+/// `PICK`'s node-drag or `ORBIT`'s camera drag, both of which live on the left button.
+pub const MARQUEE: ActionId = "MARQUEE";
+pub const TOGGLE_CAMERA: ActionId = "TOGGLE_CAMERA";
+pub const FLY_FORWARD: ActionId = "FLY_FORWARD";
+pub const FLY_BACK: ActionId = "FLY_BACK";
+pub const FLY_LEFT: ActionId = "FLY_LEFT";
+pub const FLY_RIGHT: ActionId = "FLY_RIGHT";
+pub const FLY_UP: ActionId = "FLY_UP";
+pub const FLY_DOWN: ActionId = "FLY_DOWN";
+
+/// A raw winit input capable of driving an action: a button-like source (mouse button, single
+/// character key, or named key) or a continuous gesture (cursor drag delta while the left mouse
+/// button is held, or the scroll wheel).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputSource {
+    MouseButton(MouseButton),
+    Character(char),
+    NamedKey(NamedKey),
+    MouseDrag,
+    MouseWheel,
+}
+
+impl InputSource {
+    fn matches_key(&self, logical_key: Key<&str>) -> bool {
+        match (self, logical_key) {
+            (InputSource::Character(c), Key::Character(s)) => {
+                s.chars().next().is_some_and(|k| k.eq_ignore_ascii_case(c))
+            }
+            (InputSource::NamedKey(named), Key::Named(k)) => *named == k,
+            _ => false,
+        }
+    }
+}
+
+/// An `Axis` action's binding: which gesture drives it, a scale applied to the raw delta (e.g.
+/// mouse sensitivity), and a deadzone below which a sample is ignored rather than accumulated.
+#[derive(Debug, Clone, Copy)]
+pub struct AxisBinding {
+    pub source: InputSource,
+    pub scale: f32,
+    pub deadzone: f32,
+}
+
+/// A `Button` action's binding: which raw input press/release drives it.
+#[derive(Debug, Clone, Copy)]
+pub struct ButtonBinding {
+    pub source: InputSource,
+}
+
+/// The set of action-to-input bindings currently in effect, built via `ActionLayoutBuilder`.
+/// Exposed mutably so a settings panel can rebind an action or adjust an axis's `scale` at
+/// runtime.
+#[derive(Debug, Clone, Default)]
+pub struct ActionLayout {
+    pub axes: HashMap<ActionId, AxisBinding>,
+    pub buttons: HashMap<ActionId, ButtonBinding>,
+}
+
+impl ActionLayout {
+    /// The bindings this crate has always shipped with (orbit drag, scroll zoom, left-click pick,
+    /// Tab to toggle camera mode, WASD/space/shift to fly) - equivalent to what was previously
+    /// hardcoded directly into `CameraController`/`State::handle_event`.
+    pub fn default_bindings() -> Self {
+        ActionLayoutBuilder::new()
+            .axis(ORBIT, InputSource::MouseDrag, 0.005, 0.0)
+            .axis(ZOOM, InputSource::MouseWheel, 1.0, 0.0)
+            .button(PICK, InputSource::MouseButton(MouseButton::Left))
+            .button(MARQUEE, InputSource::MouseButton(MouseButton::Right))
+            .button(TOGGLE_CAMERA, InputSource::NamedKey(NamedKey::Tab))
+            .button(FLY_FORWARD, InputSource::Character('w'))
+            .button(FLY_BACK, InputSource::Character('s'))
+            .button(FLY_LEFT, InputSource::Character('a'))
+            .button(FLY_RIGHT, InputSource::Character('d'))
+            .button(FLY_UP, InputSource::NamedKey(NamedKey::Space))
+            .button(FLY_DOWN, InputSource::NamedKey(NamedKey::Shift))
+            .build()
+    }
+}
+
+/// Builder for an `ActionLayout` - registers each named action's binding one at a time instead of
+/// constructing the two `HashMap`s by hand.
+pub struct ActionLayoutBuilder {
+    axes: HashMap<ActionId, AxisBinding>,
+    buttons: HashMap<ActionId, ButtonBinding>,
+}
+
+impl ActionLayoutBuilder {
+    pub fn new() -> Self {
+        Self {
+            axes: HashMap::new(),
+            buttons: HashMap::new(),
+        }
+    }
+
+    pub fn axis(mut self, action: ActionId, source: InputSource, scale: f32, deadzone: f32) -> Self {
+        self.axes.insert(action, AxisBinding { source, scale, deadzone });
+        self
+    }
+
+    pub fn button(mut self, action: ActionId, source: InputSource) -> Self {
+        self.buttons.insert(action, ButtonBinding { source });
+        self
+    }
+
+    pub fn build(self) -> ActionLayout {
+        ActionLayout {
+            axes: self.axes,
+            buttons: self.buttons,
+        }
+    }
+}
+
+/// Translates raw `WindowEvent`s into resolved action values per `layout`, and holds the
+/// resulting state until a caller consumes it. Axis values are accumulated deltas (drained by
+/// `take_axis`); button values track both the held state (`held`) and one-shot press/release
+/// edges (`take_just_pressed`/`take_just_released`).
+pub struct InputMap {
+    layout: ActionLayout,
+    mouse_dragging: bool,
+    last_mouse_pos: Option<(f32, f32)>,
+    axis_deltas: HashMap<ActionId, (f32, f32)>,
+    button_held: HashMap<ActionId, bool>,
+    button_just_pressed: HashMap<ActionId, bool>,
+    button_just_released: HashMap<ActionId, bool>,
+}
+
+impl InputMap {
+    pub fn new(layout: ActionLayout) -> Self {
+        Self {
+            layout,
+            mouse_dragging: false,
+            last_mouse_pos: None,
+            axis_deltas: HashMap::new(),
+            button_held: HashMap::new(),
+            button_just_pressed: HashMap::new(),
+            button_just_released: HashMap::new(),
+        }
+    }
+
+    pub fn layout(&self) -> &ActionLayout {
+        &self.layout
+    }
+
+    pub fn layout_mut(&mut self) -> &mut ActionLayout {
+        &mut self.layout
+    }
+
+    /// Feed one `WindowEvent` through the layout, updating whichever actions it's bound to.
+    /// Returns whether any bound action consumed it, for `State::handle_event`'s `event_used`
+    /// bookkeeping.
+    pub fn handle_event(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::MouseInput { state, button, .. } => {
+                let pressed = *state == ElementState::Pressed;
+                let mut used = false;
+                for (&action, binding) in self.layout.buttons.iter() {
+                    if binding.source == InputSource::MouseButton(*button) {
+                        Self::set_button(
+                            &mut self.button_held,
+                            &mut self.button_just_pressed,
+                            &mut self.button_just_released,
+                            action,
+                            pressed,
+                        );
+                        used = true;
+                    }
+                }
+
+                // The left button additionally gates `MouseDrag`-bound axes, regardless of
+                // whether it's also bound to a button action (e.g. `PICK`).
+                if *button == MouseButton::Left {
+                    self.mouse_dragging = pressed;
+                    if !pressed {
+                        self.last_mouse_pos = None;
+                    }
+                }
+
+                used
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                if !self.mouse_dragging {
+                    return false;
+                }
+                if let Some((last_x, last_y)) = self.last_mouse_pos {
+                    let delta_x = position.x as f32 - last_x;
+                    let delta_y = position.y as f32 - last_y;
+                    for (&action, binding) in self.layout.axes.iter() {
+                        if binding.source == InputSource::MouseDrag {
+                            Self::accumulate_axis(
+                                &mut self.axis_deltas,
+                                action,
+                                delta_x * binding.scale,
+                                delta_y * binding.scale,
+                                binding.deadzone,
+                            );
+                        }
+                    }
+                }
+                self.last_mouse_pos = Some((position.x as f32, position.y as f32));
+                true
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let scroll_amount = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => *y,
+                    MouseScrollDelta::PixelDelta(pos) => pos.y as f32 / 100.0,
+                };
+                for (&action, binding) in self.layout.axes.iter() {
+                    if binding.source == InputSource::MouseWheel {
+                        Self::accumulate_axis(
+                            &mut self.axis_deltas,
+                            action,
+                            scroll_amount * binding.scale,
+                            0.0,
+                            binding.deadzone,
+                        );
+                    }
+                }
+                true
+            }
+            WindowEvent::KeyboardInput { event, .. } => {
+                if event.repeat {
+                    return false;
+                }
+                let pressed = event.state == ElementState::Pressed;
+                let mut used = false;
+                for (&action, binding) in self.layout.buttons.iter() {
+                    if binding.source.matches_key(event.logical_key.as_ref()) {
+                        Self::set_button(
+                            &mut self.button_held,
+                            &mut self.button_just_pressed,
+                            &mut self.button_just_released,
+                            action,
+                            pressed,
+                        );
+                        used = true;
+                    }
+                }
+                used
+            }
+            _ => false,
+        }
+    }
+
+    fn accumulate_axis(
+        axis_deltas: &mut HashMap<ActionId, (f32, f32)>,
+        action: ActionId,
+        dx: f32,
+        dy: f32,
+        deadzone: f32,
+    ) {
+        if (dx * dx + dy * dy).sqrt() < deadzone {
+            return;
+        }
+        let entry = axis_deltas.entry(action).or_insert((0.0, 0.0));
+        entry.0 += dx;
+        entry.1 += dy;
+    }
+
+    fn set_button(
+        button_held: &mut HashMap<ActionId, bool>,
+        button_just_pressed: &mut HashMap<ActionId, bool>,
+        button_just_released: &mut HashMap<ActionId, bool>,
+        action: ActionId,
+        pressed: bool,
+    ) {
+        let was_held = button_held.get(action).copied().unwrap_or(false);
+        button_held.insert(action, pressed);
+        if pressed && !was_held {
+            button_just_pressed.insert(action, true);
+        } else if !pressed && was_held {
+            button_just_released.insert(action, true);
+        }
+    }
+
+    /// The accumulated delta for an `Axis` action since the last call, zeroed on read.
+    pub fn take_axis(&mut self, action: ActionId) -> (f32, f32) {
+        self.axis_deltas.remove(action).unwrap_or((0.0, 0.0))
+    }
+
+    /// Whether a `Button` action's bound input is currently held down.
+    pub fn held(&self, action: ActionId) -> bool {
+        self.button_held.get(action).copied().unwrap_or(false)
+    }
+
+    /// Whether a `Button` action transitioned to pressed since the last call, consumed on read.
+    pub fn take_just_pressed(&mut self, action: ActionId) -> bool {
+        self.button_just_pressed.remove(action).unwrap_or(false)
+    }
+
+    /// Whether a `Button` action transitioned to released since the last call, consumed on read.
+    pub fn take_just_released(&mut self, action: ActionId) -> bool {
+        self.button_just_released.remove(action).unwrap_or(false)
+    }
+}
+
+/// The declared type of each action this crate defines - used by the settings panel to decide
+/// whether to show a rebind control as an axis-sensitivity slider or a button-binding picker.
+pub fn action_kind(action: ActionId) -> ActionKind {
+    match action {
+        ORBIT | PAN | ZOOM => ActionKind::Axis,
+        _ => ActionKind::Button,
+    }
+}
+
+/// Every action this crate currently defines, in declaration order - used to drive the settings
+/// panel's listing without it needing to know the action set itself.
+pub const ALL_ACTIONS: &[ActionId] = &[
+    ORBIT,
+    PAN,
+    ZOOM,
+    PICK,
+    MARQUEE,
+    TOGGLE_CAMERA,
+    FLY_FORWARD,
+    FLY_BACK,
+    FLY_LEFT,
+    FLY_RIGHT,
+    FLY_UP,
+    FLY_DOWN,
+];
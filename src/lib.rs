@@ -1,6 +1,7 @@
 mod app;
 mod config;
 mod graphics;
+mod input;
 mod model;
 mod state;
 
@@ -5,14 +5,25 @@ use corridor::Network;
 fn main() {
     let args: Vec<String> = std::env::args().collect();
 
-    let config = if args.len() > 1 {
-        let network_path = &args[1];
-        let network = parse_network(network_path);
-        AppConfig::with_network(network)
+    let mut config = if args.len() > 1 {
+        let path = &args[1];
+        if path.ends_with(".gltf") || path.ends_with(".glb") {
+            AppConfig::with_gltf_path(path.clone())
+        } else if path.ends_with(".rhai") {
+            AppConfig::with_script_path(path.clone())
+        } else {
+            AppConfig::with_network(parse_network(path))
+        }
     } else {
         AppConfig::default()
     };
 
+    // A second argument imports a glTF/GLB model's meshes into whatever scene the first argument
+    // produced, instead of replacing it - e.g. `corridor network.json props.glb`.
+    if let Some(import_path) = args.get(2) {
+        config = config.with_import_gltf_path(import_path.clone());
+    }
+
     corridor::run_app(config);
 }
 
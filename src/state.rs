@@ -1,59 +1,50 @@
-use crate::graphics::scene::{self, Camera, Scene};
+use crate::graphics::scene::{
+    self, Camera, CameraMode, FlyCamera, FlyDirection, ForceLayout, Scene, ScriptedScene,
+    ViewProjection,
+};
 use crate::graphics::scene::network::update_network_edges;
 use crate::graphics::{
-    CameraBuffer, GpuContext, InstanceBuffer, InstanceData, LightingBuffer, LightingControls,
-    LightingSettings, MeshBuffers, Pipeline, render_scene,
+    CameraBuffer, Frustum, GpuContext, HDR_COLOR_FORMAT, InstanceBuffer, InstanceData, JointBuffer,
+    LightingBuffer, LightingControls, LightingSettings, MeshBuffers, PickingPass, Pipeline,
+    RenderGraph, RenderTarget, ResourceId, ShadowFilterMode, ShadowMap, ToneMapPass, UNSKINNED,
+    Viewport, cull_visible_nodes, light_view_proj, render_scene,
 };
 use crate::graphics::{CameraDebugInfo, EguiIntegration, RenderStats, panels};
+use crate::input::{
+    ActionLayout, FLY_BACK, FLY_DOWN, FLY_FORWARD, FLY_LEFT, FLY_RIGHT, FLY_UP, InputMap, MARQUEE,
+    ORBIT, PICK, TOGGLE_CAMERA, ZOOM,
+};
 use crate::model::Network;
-use glam::Vec3;
+use glam::{Mat4, Vec3};
 use instant::Instant;
 use std::collections::VecDeque;
-use winit::event::{ElementState, MouseButton, WindowEvent};
+use winit::event::WindowEvent;
 use winit::window::Window;
 
-#[derive(Default)]
-struct CameraController {
-    mouse_dragging: bool,
-    last_mouse_pos: Option<(f32, f32)>,
-}
+/// Which `FlyDirection` each fly-movement action drives, so `State::handle_event` can sync
+/// `FlyCamera`'s pressed flags from the resolved action states in one pass.
+const FLY_BINDINGS: &[(crate::input::ActionId, FlyDirection)] = &[
+    (FLY_FORWARD, FlyDirection::Forward),
+    (FLY_BACK, FlyDirection::Back),
+    (FLY_LEFT, FlyDirection::Left),
+    (FLY_RIGHT, FlyDirection::Right),
+    (FLY_UP, FlyDirection::Up),
+    (FLY_DOWN, FlyDirection::Down),
+];
+
+/// Apply the resolved `ORBIT`/`ZOOM` action values to whichever camera `mode` says is active.
+/// Both cameras keep their own orientation state, so switching modes mid-drag just starts feeding
+/// the other one from the next resolved axis value on.
+fn apply_camera_input(mode: CameraMode, camera: &mut Camera, flycam: &mut FlyCamera, input: &mut InputMap) {
+    let (orbit_dx, orbit_dy) = input.take_axis(ORBIT);
+    match mode {
+        CameraMode::Orbit => camera.handle_mouse_drag(orbit_dx, orbit_dy),
+        CameraMode::Fly => flycam.handle_mouse_drag(orbit_dx, orbit_dy),
+    }
 
-impl CameraController {
-    fn handle_event(&mut self, camera: &mut Camera, event: &WindowEvent) -> bool {
-        match event {
-            WindowEvent::MouseInput {
-                state,
-                button: MouseButton::Left,
-                ..
-            } => {
-                self.mouse_dragging = *state == ElementState::Pressed;
-                if !self.mouse_dragging {
-                    self.last_mouse_pos = None;
-                }
-                true
-            }
-            WindowEvent::CursorMoved { position, .. } => {
-                if self.mouse_dragging {
-                    if let Some((last_x, last_y)) = self.last_mouse_pos {
-                        let delta_x = position.x as f32 - last_x;
-                        let delta_y = position.y as f32 - last_y;
-                        camera.handle_mouse_drag(delta_x * 0.005, delta_y * 0.005);
-                    }
-                    self.last_mouse_pos = Some((position.x as f32, position.y as f32));
-                    return true;
-                }
-                false
-            }
-            WindowEvent::MouseWheel { delta, .. } => {
-                let scroll_amount = match delta {
-                    winit::event::MouseScrollDelta::LineDelta(_, y) => *y,
-                    winit::event::MouseScrollDelta::PixelDelta(pos) => pos.y as f32 / 100.0,
-                };
-                camera.handle_scroll(scroll_amount);
-                true
-            }
-            _ => false,
-        }
+    let (zoom, _) = input.take_axis(ZOOM);
+    if mode == CameraMode::Orbit {
+        camera.handle_scroll(zoom);
     }
 }
 
@@ -63,33 +54,120 @@ pub struct State {
     pipeline: Pipeline,
     mesh_buffers: Vec<MeshBuffers>,
     instance_buffer: InstanceBuffer,
+    /// Instance data for only the nodes that survived frustum culling this frame, compacted (but
+    /// not reordered) so the main color pass's draw ranges index this buffer instead of
+    /// `instance_buffer`. Kept separate from `instance_buffer` because the shadow pass uploads
+    /// and draws the full, uncompacted scene within the same frame/encoder.
+    visible_instance_buffer: InstanceBuffer,
     camera_buffer: CameraBuffer,
     lighting_buffer: LightingBuffer,
+    shadow_map: ShadowMap,
+    shadow_bind_group: wgpu::BindGroup,
+    /// Joint-matrix palette for this frame's skinned nodes, rebuilt every frame in `render` from
+    /// `Scene::skinned_joint_matrices` - see `InstanceData::skin_offset`.
+    joint_buffer: JointBuffer,
+    tone_map: ToneMapPass,
+    /// Pixel-accurate GPU hover pick, run alongside the cheaper CPU/BVH ray pick on every cursor
+    /// move. Its readback lags a frame or two behind the request, so `render` only overrides
+    /// `scene.picking.hovered_node` with its result once `poll_result` actually has one - until
+    /// then the immediate CPU pick (see `handle_event`) stands in.
+    picking_pass: PickingPass,
     ui: EguiIntegration,
     scene: Scene,
+    /// Optional Fruchterman-Reingold relaxation of `scene`'s network pillars, stepped from
+    /// `update` while `running`. Rebuilt once from `scene`'s pillar/edge structure in `new`.
+    layout: ForceLayout,
+    /// Set when `scene` was built from a `.rhai` script (see `AppConfig::script_path`); `update`
+    /// polls it for file changes and calls its per-frame `update(time)` hook. `None` for a
+    /// network/glTF/demo scene, which never re-evaluates after `new`.
+    scripted_scene: Option<ScriptedScene>,
+    /// Seconds of wall-clock time accumulated since `new`, passed to `scripted_scene`'s
+    /// `update(time)` hook. Only advanced while a script is loaded - nothing else reads it.
+    scene_time: f32,
+    /// Seconds of wall-clock time accumulated since `new`, sampled into every skinned node's
+    /// `AnimationClip` in `render` - unlike `scene_time`, always advances regardless of whether a
+    /// script is loaded.
+    animation_time: f32,
     camera: Camera,
+    flycam: FlyCamera,
+    /// Which of `camera`/`flycam` is currently driving `render`'s view-projection, toggled with
+    /// Tab. Node-drag picking is disabled while flying, since WASD movement and drag-picking both
+    /// want the left mouse button/cursor for different things.
+    camera_mode: CameraMode,
     lighting_controls: LightingControls,
-    camera_controller: CameraController,
+    /// Resolves raw `WindowEvent`s into the named actions (`ORBIT`, `PICK`, `TOGGLE_CAMERA`, ...)
+    /// that drive the cameras and node-drag picking - see `crate::input`.
+    input_map: InputMap,
     window: std::sync::Arc<Window>,
     last_cursor_position: Option<winit::dpi::PhysicalPosition<f64>>,
+    /// Window-space origin of an in-progress `MARQUEE` drag, set on press and consumed (along
+    /// with `last_cursor_position` as the other corner) on release to fire off
+    /// `PickingPass::request_pick_region`.
+    marquee_origin: Option<(f32, f32)>,
     last_frame_time: Instant,
     frame_times: VecDeque<f32>,
     frame_count: u64,
+    /// Cached per-node `InstanceData`, parallel to `scene.nodes`. Rebuilt only for nodes
+    /// `scene.dirty` flags plus whichever nodes carry a hover/drag highlight this frame or did
+    /// last frame, so a static city-scale network doesn't re-walk every node every frame.
+    instance_data: Vec<InstanceData>,
+    highlighted_last_frame: Vec<u32>,
+    /// Toggle for camera frustum culling before batched instance submission, surfaced in the
+    /// render-stats panel.
+    cull_enabled: bool,
 }
 
 impl State {
-    pub async fn new(window: std::sync::Arc<Window>, network: Option<Network>) -> Self {
+    pub async fn new(
+        window: std::sync::Arc<Window>,
+        network: Option<Network>,
+        gltf_path: Option<String>,
+        script_path: Option<String>,
+        import_gltf_path: Option<String>,
+        msaa_samples: u32,
+    ) -> Self {
         let raw_size = window.inner_size();
         let size = winit::dpi::PhysicalSize::new(raw_size.width.max(1), raw_size.height.max(1));
-        let gpu = GpuContext::new(&window).await;
+        let gpu = GpuContext::new(&window, msaa_samples).await;
+
+        // The main pipeline draws into the offscreen HDR target, not the swapchain directly -
+        // `tone_map` samples it back down to the surface format afterward.
+        let pipeline = Pipeline::new(&gpu.device, HDR_COLOR_FORMAT, gpu.sample_count);
+
+        // Create scene from a script if one was given, else from a glTF asset, else from the
+        // network, else fall back to the procedural demo scene.
+        let mut scripted_scene = None;
+        let mut scene = if let Some(script_path) = script_path {
+            let (script, scene) = ScriptedScene::load(&script_path);
+            scripted_scene = Some(script);
+            scene
+        } else if let Some(gltf_path) = gltf_path {
+            Scene::from_gltf(&gltf_path)
+        } else {
+            match network {
+                Some(network) => scene::network::network_to_scene(&network),
+                None => scene::demo::create_demo_scene(),
+            }
+        };
 
-        let pipeline = Pipeline::new(&gpu.device, gpu.config.format);
+        // Append an imported glTF/GLB's meshes/materials as their own nodes so the model is
+        // actually visible, rather than leaving them importable-but-unreferenced.
+        if let Some(import_gltf_path) = import_gltf_path {
+            for imported in scene::gltf_import::import_meshes_into_scene(&mut scene, &import_gltf_path) {
+                scene.nodes.push(scene::SceneNode::new(
+                    imported.mesh_id,
+                    imported.material_id,
+                    scene::Transform::identity(),
+                    true,
+                ));
+            }
+        }
 
-        // Create scene from network or use demo scene
-        let scene = match network {
-            Some(network) => scene::network::network_to_scene(&network),
-            None => scene::demo::create_demo_scene(),
-        };
+        // Every node starts dirty so the first frame builds the full instance cache.
+        scene.dirty = vec![true; scene.nodes.len()];
+
+        let mut layout = ForceLayout::new();
+        layout.rebuild(&scene);
 
         // Create mesh buffers for all meshes in the scene
         let mesh_buffers: Vec<MeshBuffers> = scene
@@ -100,6 +178,7 @@ impl State {
 
         // Create instance buffer with capacity for all nodes
         let instance_buffer = InstanceBuffer::new(&gpu.device, 1000);
+        let visible_instance_buffer = InstanceBuffer::new(&gpu.device, 1000);
 
         // Create camera buffer
         let camera_buffer = CameraBuffer::new(&gpu.device, &pipeline.camera_bind_group_layout);
@@ -111,9 +190,31 @@ impl State {
         // Create camera
         let aspect_ratio = size.width as f32 / size.height as f32;
         let camera = Camera::new(aspect_ratio);
+        let flycam = FlyCamera::new(aspect_ratio);
 
         let lighting_controls = LightingControls::default();
 
+        // Shadow map reuses the camera bind group layout shape (a single vertex-visible
+        // view-projection uniform), since the light's view-projection is structurally identical.
+        let shadow_map = ShadowMap::new(
+            &gpu.device,
+            &pipeline.camera_bind_group_layout,
+            lighting_controls.shadow_map_resolution,
+        );
+        let shadow_bind_group =
+            shadow_map.create_bind_group(&gpu.device, &pipeline.shadow_bind_group_layout);
+
+        let joint_buffer = JointBuffer::new(&gpu.device, &pipeline.joint_bind_group_layout);
+
+        let tone_map = ToneMapPass::new(&gpu.device, gpu.config.format, &gpu.hdr_color_view);
+
+        let picking_pass = PickingPass::new(
+            &gpu.device,
+            &pipeline.camera_bind_group_layout,
+            size.width,
+            size.height,
+        );
+
         let ui = EguiIntegration::new(&gpu.device, gpu.config.format, &window);
 
         Self {
@@ -122,18 +223,34 @@ impl State {
             pipeline,
             mesh_buffers,
             instance_buffer,
+            visible_instance_buffer,
             camera_buffer,
             lighting_buffer,
+            shadow_map,
+            shadow_bind_group,
+            joint_buffer,
+            tone_map,
+            picking_pass,
             ui,
             scene,
+            layout,
+            scripted_scene,
+            scene_time: 0.0,
+            animation_time: 0.0,
             camera,
+            flycam,
+            camera_mode: CameraMode::Orbit,
             lighting_controls,
-            camera_controller: CameraController::default(),
+            input_map: InputMap::new(ActionLayout::default_bindings()),
             window,
             last_cursor_position: None,
+            marquee_origin: None,
             last_frame_time: Instant::now(),
             frame_times: VecDeque::with_capacity(300),
             frame_count: 0,
+            instance_data: Vec::new(),
+            highlighted_last_frame: Vec::new(),
+            cull_enabled: true,
         }
     }
 
@@ -145,84 +262,126 @@ impl State {
 
         let mut event_used = false;
 
-        // Track cursor position for picking
+        // Resolve the raw event into action values before anything below reads them - see
+        // `crate::input`.
+        if self.input_map.handle_event(event) {
+            event_used = true;
+        }
+
+        // Track cursor position for picking. Node hover/drag picking only applies in orbit mode -
+        // while flying, the cursor isn't pointing a drag-pick ray, it's aiming the camera itself.
         if let WindowEvent::CursorMoved { position, .. } = event {
             self.last_cursor_position = Some(*position);
 
-            // Immediate CPU-based hover detection
-            let (ray_origin, ray_dir) = self.camera.screen_to_world_ray(
-                position.x as f32,
-                position.y as f32,
-                self.size.width as f32,
-                self.size.height as f32,
-            );
-            let hovered = self.scene.cpu_pick_ray(ray_origin, ray_dir);
-            self.scene.picking.update_hovered_node(hovered);
+            if self.camera_mode == CameraMode::Orbit {
+                // Immediate hover detection, BVH-accelerated so this stays cheap on every mouse
+                // move even for scenes with many nodes.
+                let ray = self.camera.screen_to_world_ray_cached(
+                    position.x as f32,
+                    position.y as f32,
+                    self.size.width as f32,
+                    self.size.height as f32,
+                );
+                let hovered = self.scene.pick_ray_bvh(ray.origin, ray.dir);
+                self.scene.picking.update_hovered_node(hovered);
+
+                // Also queue a pixel-accurate GPU pick under the cursor; `render` swaps its
+                // result in once the readback resolves (see `picking_pass`'s doc comment),
+                // refining the CPU/BVH estimate above without blocking on it.
+                self.picking_pass.request_pick(
+                    position.x as u32,
+                    position.y as u32,
+                    self.window.scale_factor(),
+                );
 
-            if self.scene.picking.is_dragging() {
-                let pos = (position.x as f32, position.y as f32);
-                self.scene.picking.update_drag(pos);
+                if self.scene.picking.is_dragging() {
+                    let pos = (position.x as f32, position.y as f32);
+                    self.scene.picking.update_drag(pos);
 
-                // Update dragged node position if node is locked
-                if self.scene.picking.is_node_locked() {
-                    if let Some(node_id) = self.scene.picking.picked_node {
-                        self.update_dragged_node_position(node_id);
+                    // Update dragged node position if node is locked
+                    if self.scene.picking.is_node_locked() {
+                        if let Some(node_id) = self.scene.picking.picked_node {
+                            self.update_dragged_node_position(node_id);
+                        }
+                        event_used = true;
                     }
-                    event_used = true;
                 }
             }
         }
 
-        if let WindowEvent::KeyboardInput { event, .. } = event {
-            match event.logical_key.as_ref() {
-                _ => {}
-            }
+        if self.input_map.take_just_pressed(TOGGLE_CAMERA) {
+            self.camera_mode = match self.camera_mode {
+                CameraMode::Orbit => CameraMode::Fly,
+                CameraMode::Fly => CameraMode::Orbit,
+            };
         }
 
-        // Handle picking on left click (if not currently dragging for camera)
-        if let WindowEvent::MouseInput {
-            state: button_state,
-            button: MouseButton::Left,
-            ..
-        } = event
-        {
-            match button_state {
-                ElementState::Pressed => {
-                    // Left mouse pressed - start drag
-                    if let Some(pos) = self.last_cursor_position {
-                        let pos = (pos.x as f32, pos.y as f32);
-                        self.scene.picking.start_drag(pos);
-
-                        // Use current hover state to determine drag behavior
-                        if let Some(hovered_id) = self.scene.picking.hovered_node {
-                            if let Some(node) = self.scene.nodes.get(hovered_id as usize) {
-                                if node.selectable {
-                                    // Lock this node immediately for dragging
-                                    self.lock_node_for_drag(hovered_id);
-                                    event_used = true;
-                                }
+        // Sync the fly camera's pressed flags from the resolved `FLY_*` actions every event -
+        // cheap, and means `FlyCamera` doesn't need to know this crate's keyboard layout itself.
+        for &(action, direction) in FLY_BINDINGS {
+            self.flycam
+                .set_direction_pressed(direction, self.input_map.held(action));
+        }
+
+        // Handle picking on left click (if not currently dragging for camera). Disabled while
+        // flying - see the field doc on `camera_mode`.
+        if self.camera_mode == CameraMode::Orbit {
+            if self.input_map.take_just_pressed(PICK) {
+                // Left mouse pressed - start drag
+                if let Some(pos) = self.last_cursor_position {
+                    let pos = (pos.x as f32, pos.y as f32);
+                    self.scene.picking.start_drag(pos);
+
+                    // Use current hover state to determine drag behavior
+                    if let Some(hovered_id) = self.scene.picking.hovered_node {
+                        if let Some(node) = self.scene.nodes.get(hovered_id as usize) {
+                            if node.selectable {
+                                // Lock this node immediately for dragging
+                                self.lock_node_for_drag(hovered_id);
+                                event_used = true;
                             }
                         }
-                        // If no selectable node hovered, don't set event_used (camera handles it)
                     }
+                    // If no selectable node hovered, don't set event_used (camera handles it)
                 }
-                ElementState::Released => {
-                    // Left mouse released - end drag
-                    if self.scene.picking.is_dragging() {
-                        self.scene.picking.end_drag();
-                        event_used = true;
-                    }
+            }
+            if self.input_map.take_just_released(PICK) {
+                // Left mouse released - end drag
+                if self.scene.picking.is_dragging() {
+                    self.scene.picking.end_drag();
+                    event_used = true;
+                }
+            }
+
+            // Box-select on right-mouse drag: remember where it started, then resolve the
+            // rectangle against the current cursor position on release.
+            if self.input_map.take_just_pressed(MARQUEE) {
+                if let Some(pos) = self.last_cursor_position {
+                    self.marquee_origin = Some((pos.x as f32, pos.y as f32));
+                    self.scene.picking.clear_selection();
+                    event_used = true;
+                }
+            }
+            if self.input_map.take_just_released(MARQUEE) {
+                if let (Some(origin), Some(pos)) = (self.marquee_origin.take(), self.last_cursor_position) {
+                    self.picking_pass.request_pick_region(
+                        origin.0 as u32,
+                        origin.1 as u32,
+                        pos.x as u32,
+                        pos.y as u32,
+                        self.window.scale_factor(),
+                    );
+                    event_used = true;
                 }
             }
         }
 
         // Only allow camera control if we're not dragging a locked node
-        let camera_used = if self.scene.picking.is_node_locked() {
-            false
-        } else {
-            self.camera_controller.handle_event(&mut self.camera, event)
-        };
-        event_used || camera_used
+        if !self.scene.picking.is_node_locked() {
+            apply_camera_input(self.camera_mode, &mut self.camera, &mut self.flycam, &mut self.input_map);
+        }
+
+        event_used
     }
 
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
@@ -234,13 +393,34 @@ impl State {
                 .surface
                 .configure(&self.gpu.device, &self.gpu.config);
 
-            // Recreate depth texture with new size
-            self.gpu.depth_texture =
-                GpuContext::create_depth_texture(&self.gpu.device, new_size.width, new_size.height);
+            // Recreate depth + HDR + MSAA color targets at the new size (same sample count as
+            // before)
+            self.gpu.depth_texture = GpuContext::create_depth_texture(
+                &self.gpu.device,
+                new_size.width,
+                new_size.height,
+                self.gpu.sample_count,
+            );
+            self.gpu.hdr_color_view = GpuContext::create_hdr_color_target(
+                &self.gpu.device,
+                new_size.width,
+                new_size.height,
+            );
+            self.gpu.msaa_color_view = GpuContext::create_msaa_color_target(
+                &self.gpu.device,
+                new_size.width,
+                new_size.height,
+                self.gpu.sample_count,
+            );
+            self.tone_map
+                .resize(&self.gpu.device, &self.gpu.hdr_color_view);
+            self.picking_pass
+                .resize(&self.gpu.device, new_size.width, new_size.height);
 
             // Update camera aspect ratio
             let aspect_ratio = new_size.width as f32 / new_size.height as f32;
             self.camera.update_aspect_ratio(aspect_ratio);
+            self.flycam.update_aspect_ratio(aspect_ratio);
         }
     }
 
@@ -255,10 +435,51 @@ impl State {
             }
         }
 
+        if self.camera_mode == CameraMode::Fly {
+            self.flycam.tick(delta);
+        }
+
+        self.animation_time += delta;
+
+        // Relax the network layout, if running, pinning whichever pillar the user is currently
+        // dragging so manual placement always wins over the simulation.
+        let pinned = scene::pinned_pillar(&self.scene);
+        for node_id in self.layout.step(&mut self.scene, pinned, delta) {
+            update_network_edges(&mut self.scene, node_id);
+        }
+
+        // Re-evaluate the scene script on file change and run its per-frame hook, if loaded -
+        // see `ScriptedScene`.
+        if let Some(script) = &mut self.scripted_scene {
+            script.reload_if_changed(&mut self.scene);
+
+            // A reload may have added/removed meshes, so the GPU-side buffers (indexed by
+            // `mesh_id`) need rebuilding to match - same construction as `new`.
+            if self.mesh_buffers.len() != self.scene.meshes.len() {
+                self.mesh_buffers = self
+                    .scene
+                    .meshes
+                    .iter()
+                    .map(|mesh| MeshBuffers::from_mesh(&self.gpu.device, mesh))
+                    .collect();
+            }
+
+            self.scene_time += delta;
+            script.update(&mut self.scene, self.scene_time);
+        }
+
         self.last_frame_time = now;
         self.frame_count += 1;
     }
 
+    /// The camera currently driving `render`'s view-projection matrix, whichever mode is active.
+    fn active_camera(&self) -> &dyn ViewProjection {
+        match self.camera_mode {
+            CameraMode::Orbit => &self.camera,
+            CameraMode::Fly => &self.flycam,
+        }
+    }
+
     pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
         let surface_output = self.gpu.surface.get_current_texture()?;
         let view = surface_output
@@ -271,57 +492,142 @@ impl State {
             .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
 
         // Prepare per-frame buffers once for all passes
-        let view_proj = self.camera.view_projection_matrix();
+        let view_proj = self.active_camera().view_projection_matrix();
         self.camera_buffer.update(&self.gpu.queue, &view_proj);
 
-        let instance_data: Vec<InstanceData> = self
-            .scene
-            .nodes
-            .iter()
-            .enumerate()
-            .map(|(idx, node)| {
-                let node_id = idx as u32;
-                let matrix = self.scene.compute_world_transform(node_id);
-                let color = self.scene.materials[node.material_id].color;
+        // Nodes currently carrying a hover/drag highlight - these need their flags re-evaluated
+        // every frame regardless of `scene.dirty`, since highlighting changes without the node
+        // itself moving.
+        let mut highlighted_now: Vec<u32> = Vec::new();
+        if self.scene.picking.is_dragging() {
+            if let Some(dragged_id) = self.scene.picking.picked_node {
+                highlighted_now.push(dragged_id);
+                highlighted_now.extend(self.scene.get_descendants(dragged_id));
+            }
+        } else if let Some(hovered_id) = self.scene.picking.hovered_node {
+            highlighted_now.push(hovered_id);
+        }
 
-                // Compute state flags
-                let mut state_flags = 0u32;
+        // Rebuild the cache wholesale if the node count changed (scene (re)loaded).
+        if self.instance_data.len() != self.scene.nodes.len() {
+            self.instance_data = vec![
+                InstanceData {
+                    matrix: Mat4::IDENTITY.to_cols_array_2d(),
+                    color: [0.0; 4],
+                    skin_offset: UNSKINNED,
+                };
+                self.scene.nodes.len()
+            ];
+            self.scene.dirty.iter_mut().for_each(|flag| *flag = true);
+        }
 
-                if self.scene.picking.is_dragging() {
-                    if let Some(dragged_id) = self.scene.picking.picked_node {
-                        // Highlight dragged node AND all its children
-                        if node_id == dragged_id || self.is_descendant_of(node_id, dragged_id) {
-                            state_flags |= 0x02; // STATE_DRAGGING
-                        }
-                    }
-                } else if self.scene.picking.hovered_node == Some(node_id) {
-                    state_flags |= 0x01; // STATE_HOVERED
-                }
+        // Every skinned node's joint palette is re-sampled every frame (animation advances even
+        // when the node itself isn't `dirty`), packed contiguously into one upload to
+        // `joint_buffer` and looked up per-instance via `InstanceData::skin_offset`.
+        let mut joint_palette: Vec<[[f32; 4]; 4]> = Vec::new();
+        let mut skin_offsets: std::collections::HashMap<u32, u32> = std::collections::HashMap::new();
+        for (idx, node) in self.scene.nodes.iter().enumerate() {
+            if node.skeleton_id.is_none() {
+                continue;
+            }
+            if let Some(matrices) = self
+                .scene
+                .skinned_joint_matrices(idx as u32, self.animation_time)
+            {
+                skin_offsets.insert(idx as u32, joint_palette.len() as u32);
+                joint_palette.extend(matrices);
+            }
+        }
+        self.joint_buffer.update(
+            &self.gpu.device,
+            &self.gpu.queue,
+            &self.pipeline.joint_bind_group_layout,
+            &joint_palette,
+        );
 
-                InstanceData {
-                    matrix,
-                    color,
-                    state_flags,
-                    _padding: [0, 0, 0],
-                }
-            })
+        let mut to_update: std::collections::HashSet<u32> =
+            self.highlighted_last_frame.iter().copied().collect();
+        to_update.extend(highlighted_now.iter().copied());
+        to_update.extend(skin_offsets.keys().copied());
+        for (idx, dirty) in self.scene.dirty.iter().enumerate() {
+            if *dirty {
+                to_update.insert(idx as u32);
+            }
+        }
+
+        // One linear pass over the nodes `dirty` flagged, instead of a recursive parent-chain
+        // walk per node below.
+        self.scene.update_transforms();
+
+        for node_id in to_update {
+            let matrix = self.scene.world_transforms[node_id as usize];
+            let node = &self.scene.nodes[node_id as usize];
+            let color = self.scene.materials[node.material_id].color;
+
+            self.instance_data[node_id as usize] = InstanceData {
+                matrix,
+                color,
+                skin_offset: skin_offsets.get(&node_id).copied().unwrap_or(UNSKINNED),
+            };
+        }
+
+        self.scene.dirty.iter_mut().for_each(|flag| *flag = false);
+        self.highlighted_last_frame = highlighted_now;
+
+        self.instance_buffer
+            .update(&self.gpu.device, &self.gpu.queue, &self.instance_data);
+
+        // Cull nodes outside the camera's view before the main color pass draws them - the
+        // shadow pass above already drew the full scene against `instance_buffer` directly, since
+        // an off-screen node can still cast an on-screen shadow. Filtering preserves relative
+        // order, so same-mesh nodes stay contiguous for `draw_batched_instances`.
+        let visible: Vec<u32> = if self.cull_enabled {
+            let frustum = Frustum::from_view_proj(Mat4::from_cols_array_2d(&view_proj));
+            cull_visible_nodes(&self.scene, &frustum)
+        } else {
+            (0..self.scene.nodes.len() as u32).collect()
+        };
+        let culled_count = self.scene.nodes.len() - visible.len();
+
+        let visible_instance_data: Vec<InstanceData> = visible
+            .iter()
+            .map(|&node_id| self.instance_data[node_id as usize])
             .collect();
-        self.instance_buffer.update(&self.gpu.queue, &instance_data);
-
-        // Calculate camera position from spherical coordinates (UI readout)
-        let cam_x = self.camera.target.x
-            + self.camera.distance * self.camera.pitch.cos() * self.camera.yaw.sin();
-        let cam_y = self.camera.target.y + self.camera.distance * self.camera.pitch.sin();
-        let cam_z = self.camera.target.z
-            + self.camera.distance * self.camera.pitch.cos() * self.camera.yaw.cos();
-
-        let camera_debug = CameraDebugInfo {
-            position: [cam_x, cam_y, cam_z],
-            target: self.camera.target.to_array(),
-            yaw: self.camera.yaw,
-            pitch: self.camera.pitch,
-            distance: self.camera.distance,
-            object_count: self.scene.nodes.len(),
+        self.visible_instance_buffer.update(
+            &self.gpu.device,
+            &self.gpu.queue,
+            &visible_instance_data,
+        );
+
+        // Camera readout for the UI panel - shape differs per mode since orbit tracks a target +
+        // distance while fly just has a free position, so report whichever fields the active
+        // mode actually has and fill the rest with its nearest equivalent (pan/tilt for yaw/pitch,
+        // zero distance since there's no orbit target to be distant from).
+        let camera_debug = match self.camera_mode {
+            CameraMode::Orbit => {
+                let cam_x = self.camera.target.x
+                    + self.camera.distance * self.camera.pitch.cos() * self.camera.yaw.sin();
+                let cam_y = self.camera.target.y + self.camera.distance * self.camera.pitch.sin();
+                let cam_z = self.camera.target.z
+                    + self.camera.distance * self.camera.pitch.cos() * self.camera.yaw.cos();
+
+                CameraDebugInfo {
+                    position: [cam_x, cam_y, cam_z],
+                    target: self.camera.target.to_array(),
+                    yaw: self.camera.yaw,
+                    pitch: self.camera.pitch,
+                    distance: self.camera.distance,
+                    object_count: self.scene.nodes.len(),
+                }
+            }
+            CameraMode::Fly => CameraDebugInfo {
+                position: self.flycam.position.to_array(),
+                target: self.flycam.position.to_array(),
+                yaw: self.flycam.pan,
+                pitch: self.flycam.tilt,
+                distance: 0.0,
+                object_count: self.scene.nodes.len(),
+            },
         };
 
         // Calculate FPS from frame times
@@ -375,6 +681,8 @@ impl State {
             node_count: self.scene.nodes.len(),
             vertex_count,
             material_count: self.scene.materials.len(),
+            drawn_count: visible.len(),
+            culled_count,
             current_fps,
             avg_fps_1s,
             avg_fps_5s,
@@ -382,7 +690,11 @@ impl State {
 
         let prepared_ui = {
             let lighting_controls = &mut self.lighting_controls;
-            let hovered_node_id = self.scene.picking.hovered_node;
+            let cull_enabled = &mut self.cull_enabled;
+            let hovered_description = self.scene.picking.describe_hovered(&self.scene);
+            let selected_count = self.scene.picking.selected_nodes.len();
+            let input_layout = self.input_map.layout_mut();
+            let layout = &mut self.layout;
 
             self.ui.begin(
                 &*self.window,
@@ -396,9 +708,13 @@ impl State {
                             ui.separator();
                             panels::lighting(ui, lighting_controls);
                             ui.separator();
-                            panels::hover_info(ui, hovered_node_id);
+                            panels::hover_info(ui, hovered_description, selected_count);
+                            ui.separator();
+                            panels::render_stats(ui, &render_stats, cull_enabled);
                             ui.separator();
-                            panels::render_stats(ui, &render_stats);
+                            panels::input_bindings(ui, input_layout);
+                            ui.separator();
+                            panels::force_layout(ui, layout);
                         });
                 },
             )
@@ -407,21 +723,120 @@ impl State {
         // Sync lighting controls into engine settings for this frame
         let lighting_settings: LightingSettings = (&self.lighting_controls).into();
 
-        // Render scene
-        render_scene(
-            &mut encoder,
-            &view,
-            &self.gpu.depth_texture,
-            &self.pipeline.render_pipeline,
-            &self.mesh_buffers,
-            &self.instance_buffer,
-            &self.camera_buffer,
-            &self.lighting_buffer,
-            &self.gpu.queue,
-            &self.scene,
-            &lighting_settings.to_uniform(),
+        // Re-fit the shadow map's orthographic frustum to the scene every frame (cheap relative
+        // to rendering it, and keeps shadows tight as nodes move) and recreate it if the user
+        // changed the resolution in the lighting panel.
+        let resolution_changed = self
+            .shadow_map
+            .set_resolution(&self.gpu.device, self.lighting_controls.shadow_map_resolution);
+        if resolution_changed {
+            self.shadow_bind_group = self
+                .shadow_map
+                .create_bind_group(&self.gpu.device, &self.pipeline.shadow_bind_group_layout);
+        }
+
+        let light_vp =
+            light_view_proj(lighting_settings.sun_direction, &self.scene.bounds()).to_cols_array_2d();
+        self.shadow_map.light_buffer.update(&self.gpu.queue, &light_vp);
+        let lighting_uniform = lighting_settings.to_uniform(&light_vp);
+
+        // Schedule the frame as a small render graph instead of calling each pass directly:
+        // shadow map -> scene (reads the shadow map) -> tone mapping (reads the HDR target the
+        // scene pass wrote). Declaring the dependency means adding a future pass (bloom, SSAO)
+        // is a new node rather than another hand-ordered call in this function.
+        let mut graph = RenderGraph::new();
+        let shadow_depth = graph.resource();
+        let hdr_color = graph.resource();
+        let ldr_swapchain = graph.resource();
+
+        // Picking reads/writes nothing the graph tracks (`&[], &[]` above), so there's no
+        // ordering reason to route these through `RenderGraph` - and doing so would require two
+        // closures held live in `graph.passes` at once, both wanting `&mut self.picking_pass`.
+        // Run them straight against the encoder instead.
+        if self.picking_pass.should_execute() {
+            self.picking_pass.execute_pick(
+                &mut encoder,
+                &self.mesh_buffers,
+                &self.instance_buffer,
+                &self.camera_buffer,
+                &self.scene,
+            );
+        }
+
+        if self.picking_pass.should_execute_region() {
+            self.picking_pass.execute_pick_region(
+                &mut encoder,
+                &self.mesh_buffers,
+                &self.instance_buffer,
+                &self.camera_buffer,
+                &self.scene,
+                &self.gpu.device,
+            );
+        }
+
+        let cast_shadows = self.lighting_controls.filter_mode != ShadowFilterMode::None;
+        if cast_shadows {
+            graph.add_pass("shadow", &[], &[shadow_depth], |encoder| {
+                // The (possibly stale) shadow map is simply never sampled when shadows are off,
+                // so skip rendering depth nobody will read this frame.
+                self.shadow_map.render(
+                    encoder,
+                    &self.mesh_buffers,
+                    &self.instance_buffer,
+                    &self.scene,
+                );
+            });
+        }
+
+        let shadow_reads: Vec<ResourceId> = if cast_shadows { vec![shadow_depth] } else { vec![] };
+        graph.add_pass(
+            "scene",
+            &shadow_reads,
+            &[hdr_color],
+            |encoder| {
+                // Single camera filling the whole window - always clears, since nothing else
+                // shares `hdr_color_view`/`depth_texture` this frame. A caller wanting
+                // split-screen or a mirror would build more `RenderTarget`s (a narrower
+                // `Viewport` with `clear: false`, or an `OffscreenTarget::target()`) and call
+                // `render_scene` again per camera.
+                let target = RenderTarget::new(
+                    &self.gpu.hdr_color_view,
+                    self.gpu.msaa_color_view.as_ref(),
+                    &self.gpu.depth_texture,
+                    Viewport::full(self.size.width, self.size.height),
+                    true,
+                );
+                render_scene(
+                    encoder,
+                    &target,
+                    &self.pipeline.render_pipeline,
+                    &self.mesh_buffers,
+                    &self.visible_instance_buffer,
+                    &self.camera_buffer,
+                    &self.lighting_buffer,
+                    &self.shadow_bind_group,
+                    &self.joint_buffer.bind_group,
+                    &self.gpu.queue,
+                    &self.scene,
+                    &lighting_uniform,
+                    &self.lighting_controls.point_lights,
+                    &visible,
+                );
+            },
         );
 
+        graph.add_pass("tonemap", &[hdr_color], &[ldr_swapchain], |encoder| {
+            self.tone_map.render(
+                encoder,
+                &self.gpu.queue,
+                &view,
+                self.lighting_controls.exposure,
+                self.lighting_controls.tone_mapping,
+            );
+        });
+
+        graph.execute(&mut encoder);
+
         // Render egui UI overlay
         self.ui.paint(
             &self.gpu.device,
@@ -433,38 +848,44 @@ impl State {
 
         self.gpu.queue.submit(std::iter::once(encoder.finish()));
 
+        // Swap in the pixel-accurate GPU pick once its readback resolves; until then the
+        // immediate CPU/BVH hover set in `handle_event` stands.
+        if let Some((node_id, _world_pos)) = self
+            .picking_pass
+            .poll_result(&self.gpu.device, self.camera_buffer.inverse_view_proj())
+        {
+            self.scene.picking.update_hovered_node(Some(node_id));
+        }
+
+        // Swap in the marquee's resolved node set once its readback completes.
+        if let Some(selected) = self.picking_pass.poll_result_region(&self.gpu.device) {
+            self.scene.picking.set_selection(selected);
+        }
+
         surface_output.present();
 
         Ok(())
     }
 
+    /// Re-cast the drag ray through the camera's inverse view-projection matrix and move the
+    /// dragged node to follow the cursor on its drag plane, pixel-accurate regardless of camera
+    /// orientation (see `PickingState::drag_to_world`).
     fn update_dragged_node_position(&mut self, node_id: u32) {
-        // Cast ray from current cursor position through camera
         if let Some(cursor_pos) = self.last_cursor_position {
-            let (origin, direction) = self.camera.screen_to_world_ray(
+            let mouse_ndc = Camera::screen_to_ndc(
                 cursor_pos.x as f32,
                 cursor_pos.y as f32,
                 self.size.width as f32,
                 self.size.height as f32,
             );
 
-            // Intersect with ground plane (Y=0)
-            let ground_plane_point = Vec3::ZERO;
-            let ground_plane_normal = Vec3::Y;
-
-            if let Some(ground_pos) = Camera::ray_plane_intersection(
-                origin,
-                direction,
-                ground_plane_point,
-                ground_plane_normal,
-            ) {
-                // Apply the stored offset
+            if let Some(hit) = self
+                .scene
+                .picking
+                .drag_to_world(mouse_ndc, self.camera_buffer.inverse_view_proj())
+            {
                 if let Some(offset) = self.scene.picking.get_drag_offset() {
-                    let new_position = ground_pos + offset;
-
-                    // Force Y to 0 (ground plane) per user preference
-                    let new_position = Vec3::new(new_position.x, 0.0, new_position.z);
-
+                    let new_position = hit - offset;
                     self.scene.update_node_position(node_id, new_position);
 
                     // Update all edges connected to this node
@@ -476,29 +897,32 @@ impl State {
 
     fn lock_node_for_drag(&mut self, node_id: u32) {
         if let Some(cursor_pos) = self.last_cursor_position {
-            let (origin, direction) = self.camera.screen_to_world_ray(
+            let mouse_ndc = Camera::screen_to_ndc(
                 cursor_pos.x as f32,
                 cursor_pos.y as f32,
                 self.size.width as f32,
                 self.size.height as f32,
             );
 
-            if let Some(click_world_pos) =
-                Camera::ray_plane_intersection(origin, direction, Vec3::ZERO, Vec3::Y)
-            {
-                if let Some(node) = self.scene.nodes.get(node_id as usize) {
-                    let offset = node.transform.position - click_world_pos;
-                    let offset = Vec3::new(offset.x, 0.0, offset.z);
+            if let Some(node) = self.scene.nodes.get(node_id as usize) {
+                let drag_plane_y = node.transform.position.y;
+                let (origin, direction) =
+                    Camera::ndc_to_world_ray(mouse_ndc, Mat4::from_cols_array_2d(self.camera_buffer.inverse_view_proj()));
+
+                if let Some(click_world_pos) = Camera::ray_plane_intersection(
+                    origin,
+                    direction,
+                    Vec3::new(0.0, drag_plane_y, 0.0),
+                    Vec3::Y,
+                ) {
+                    let offset = click_world_pos - node.transform.position;
 
                     self.scene.picking.update_picked_node(Some(node_id));
-                    self.scene.picking.lock_node_with_offset(offset);
+                    self.scene
+                        .picking
+                        .lock_node_with_offset(offset, drag_plane_y);
                 }
             }
         }
     }
-
-    fn is_descendant_of(&self, potential_child: u32, potential_ancestor: u32) -> bool {
-        let descendants = self.scene.get_descendants(potential_ancestor);
-        descendants.contains(&potential_child)
-    }
 }